@@ -0,0 +1,168 @@
+//! Detection of price inconsistencies within and across binary markets.
+
+use crate::models::{ArbitrageKind, ArbitrageOpportunity, Market};
+use crate::search::{jaccard, tokenize};
+use std::collections::HashSet;
+
+/// Minimum gap between "Yes" prices of equivalent markets worth reporting
+pub const DIVERGENCE_THRESHOLD: f64 = 0.05;
+/// Token overlap (Jaccard) above which two questions are treated as equivalent
+const SIMILARITY_THRESHOLD: f64 = 0.8;
+/// Ignore sub-cent gaps that are just rounding in quoted prices
+const MIN_PROFIT: f64 = 0.005;
+
+/// A binary market with parsed prices for its two outcomes.
+struct BinaryMarket<'a> {
+    market: &'a Market,
+    prices: [f64; 2],
+    tokens: HashSet<String>,
+}
+
+/// Finds arbitrage opportunities among `markets`, most profitable first.
+///
+/// Two situations are flagged:
+/// - A single binary market whose outcome prices sum to less than 1.0, so buying
+///   both sides locks in the difference.
+/// - Two markets asking an equivalent question whose "Yes" prices differ by more
+///   than [`DIVERGENCE_THRESHOLD`]; buying "Yes" on the cheaper market and "No" on
+///   the dearer one pays out 1.0 whichever way the question resolves. Pairs where
+///   that costs 1.0 or more, as with overrounded books, are skipped.
+pub fn detect(markets: &[Market]) -> Vec<ArbitrageOpportunity> {
+    let binaries: Vec<BinaryMarket> = markets.iter().filter_map(binary_market).collect();
+    let mut opportunities = Vec::new();
+
+    for binary in &binaries {
+        let cost = binary.prices[0] + binary.prices[1];
+        if 1.0 - cost >= MIN_PROFIT {
+            opportunities.push(ArbitrageOpportunity {
+                kind: ArbitrageKind::Underpriced,
+                market_ids: vec![binary.market.id.clone()],
+                outcomes: binary.market.outcomes.clone(),
+                prices: binary.prices.to_vec(),
+                implied_profit: 1.0 - cost,
+                description: format!(
+                    "Outcome prices of \"{}\" sum to {:.3}",
+                    binary.market.question, cost
+                ),
+            });
+        }
+    }
+
+    for (i, a) in binaries.iter().enumerate() {
+        for b in &binaries[i + 1..] {
+            if jaccard(&a.tokens, &b.tokens) < SIMILARITY_THRESHOLD {
+                continue;
+            }
+            let (cheap, dear) = if a.prices[0] <= b.prices[0] {
+                (a, b)
+            } else {
+                (b, a)
+            };
+            if dear.prices[0] - cheap.prices[0] < DIVERGENCE_THRESHOLD {
+                continue;
+            }
+
+            let cost = cheap.prices[0] + dear.prices[1];
+            if 1.0 - cost < MIN_PROFIT {
+                continue;
+            }
+            opportunities.push(ArbitrageOpportunity {
+                kind: ArbitrageKind::Divergence,
+                market_ids: vec![cheap.market.id.clone(), dear.market.id.clone()],
+                outcomes: vec![
+                    cheap.market.outcomes[0].clone(),
+                    dear.market.outcomes[1].clone(),
+                ],
+                prices: vec![cheap.prices[0], dear.prices[1]],
+                implied_profit: 1.0 - cost,
+                description: format!(
+                    "\"{}\" trades at {:.3} but \"{}\" at {:.3}",
+                    cheap.market.question, cheap.prices[0], dear.market.question, dear.prices[0]
+                ),
+            });
+        }
+    }
+
+    opportunities.sort_by(|a, b| b.implied_profit.total_cmp(&a.implied_profit));
+    opportunities
+}
+
+fn binary_market(market: &Market) -> Option<BinaryMarket<'_>> {
+    if market.outcomes.len() != 2 || market.outcome_prices.len() != 2 {
+        return None;
+    }
+    let yes = market.outcome_prices[0].parse().ok()?;
+    let no = market.outcome_prices[1].parse().ok()?;
+
+    Some(BinaryMarket {
+        market,
+        prices: [yes, no],
+        tokens: tokenize(&market.question),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn market(id: &str, question: &str, yes: &str, no: &str) -> Market {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "slug": format!("market-{id}"),
+            "question": question,
+            "active": true,
+            "closed": false,
+            "liquidity": "1000",
+            "volume": "5000",
+            "endDate": "2030-01-01T00:00:00Z",
+            "outcomes": "[\"Yes\", \"No\"]",
+            "outcomePrices": serde_json::json!([yes, no]).to_string()
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_detects_underpriced_market() {
+        let markets = vec![
+            market("1", "Will it rain tomorrow?", "0.45", "0.50"),
+            market("2", "Will the Fed cut rates?", "0.60", "0.41"),
+        ];
+
+        let opportunities = detect(&markets);
+
+        assert_eq!(opportunities.len(), 1);
+        assert_eq!(opportunities[0].kind, ArbitrageKind::Underpriced);
+        assert_eq!(opportunities[0].market_ids, vec!["1"]);
+        assert!((opportunities[0].implied_profit - 0.05).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_detects_divergent_equivalent_markets() {
+        let markets = vec![
+            market("1", "Will BTC hit $100k in 2025?", "0.70", "0.30"),
+            market("2", "Will BTC hit $100k in 2025", "0.55", "0.45"),
+            market("3", "Will ETH flip BTC?", "0.10", "0.90"),
+        ];
+
+        let opportunities = detect(&markets);
+
+        assert_eq!(opportunities.len(), 1);
+        let divergence = &opportunities[0];
+        assert_eq!(divergence.kind, ArbitrageKind::Divergence);
+        assert_eq!(divergence.market_ids, vec!["2", "1"]);
+        assert_eq!(divergence.prices, vec![0.55, 0.30]);
+        assert!((divergence.implied_profit - 0.15).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_skips_divergence_on_overrounded_books() {
+        // The "Yes" prices diverge by 0.10, but the cheap "Yes" plus the dear "No"
+        // costs 1.00, so there is nothing to lock in
+        let markets = vec![
+            market("1", "Will BTC hit $100k in 2025?", "0.70", "0.40"),
+            market("2", "Will BTC hit $100k in 2025", "0.60", "0.45"),
+        ];
+
+        assert!(detect(&markets).is_empty());
+    }
+}
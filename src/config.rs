@@ -1,19 +1,41 @@
 use anyhow::{Context, Result};
 use config::{Config as ConfigBuilder, File};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::env;
 use std::path::Path;
 use std::time::Duration;
 use tracing::warn;
 
+/// Public Gamma API host serving markets, events and tags
+pub const DEFAULT_GAMMA_URL: &str = "https://gamma-api.polymarket.com";
+/// Public CLOB host serving order books and price history
+pub const DEFAULT_CLOB_URL: &str = "https://clob.polymarket.com";
+/// Public data API host serving trades and positions
+pub const DEFAULT_DATA_API_URL: &str = "https://data-api.polymarket.com";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     pub server: ServerConfig,
     pub api: ApiConfig,
     pub cache: CacheConfig,
     pub logging: LoggingConfig,
+    #[serde(default)]
+    pub analytics: AnalyticsConfig,
+    #[serde(default)]
+    pub endpoints: EndpointsConfig,
+    #[serde(default)]
+    pub output: OutputConfig,
+    #[serde(default)]
+    pub defaults: DefaultsConfig,
+    /// Market ids served by the `markets:watchlist` resource
+    #[serde(default)]
+    pub watchlist: Vec<String>,
 }
 
+/// Accepted values for `logging.level`
+pub const LOG_LEVELS: &[&str] = &["trace", "debug", "info", "warn", "error"];
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServerConfig {
     pub name: String,
@@ -21,6 +43,16 @@ pub struct ServerConfig {
     pub description: String,
     pub max_connections: Option<u32>,
     pub timeout_seconds: u64,
+    /// Directory of TOML/JSON prompt templates served alongside the built-in prompts
+    #[serde(default)]
+    pub prompts_dir: Option<String>,
+    /// Browser origins allowed to call the HTTP transport besides loopback ones,
+    /// e.g. `https://app.example.com`
+    #[serde(default)]
+    pub allowed_origins: Vec<String>,
+    /// Whether `completion/complete` is served and advertised in `initialize`
+    #[serde(default = "default_completions")]
+    pub completions: bool,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -30,7 +62,130 @@ pub struct ApiConfig {
     pub timeout_seconds: u64,
     pub max_retries: u32,
     pub retry_delay_ms: u64,
+    #[serde(default = "default_max_retry_delay_ms")]
+    pub max_retry_delay_ms: u64,
+    /// Deprecated alias for `requests_per_second`, used only while that is 0
+    #[serde(default)]
     pub rate_limit_per_second: Option<u32>,
+    /// Maximum outgoing requests per second; 0 disables rate limiting
+    #[serde(default)]
+    pub requests_per_second: u32,
+    /// Maximum number of markets `search_markets` scans across pages
+    #[serde(default = "default_search_scan_limit")]
+    pub search_scan_limit: u32,
+    /// Pages fetched concurrently when walking paginated market listings; 1 is sequential
+    #[serde(default = "default_scan_concurrency")]
+    pub scan_concurrency: usize,
+    /// Upper bound applied to every tool's `limit` argument
+    #[serde(default = "default_max_limit")]
+    pub max_limit: u32,
+    /// Markets fetched concurrently per batch by `get_markets_batch`
+    #[serde(default = "default_batch_size")]
+    pub batch_size: usize,
+    /// Pause between consecutive batches, in milliseconds
+    #[serde(default = "default_batch_delay_ms")]
+    pub batch_delay_ms: u64,
+    /// Idle connections kept open per upstream host; 0 disables connection reuse
+    #[serde(default = "default_pool_max_idle_per_host")]
+    pub pool_max_idle_per_host: usize,
+    /// How long an idle pooled connection is kept before being closed
+    #[serde(default = "default_pool_idle_timeout_seconds")]
+    pub pool_idle_timeout_seconds: u64,
+    /// Speak HTTP/2 from the first byte instead of negotiating it; only for hosts
+    /// known to support HTTP/2
+    #[serde(default)]
+    pub http2_prior_knowledge: bool,
+    /// Proxy for all upstream traffic: `http://`, `https://`, `socks5://` or
+    /// `socks5h://`, optionally with `user:password@` credentials
+    #[serde(default)]
+    pub proxy_url: Option<String>,
+    /// `User-Agent` sent with every request
+    #[serde(default = "default_user_agent")]
+    pub user_agent: String,
+    /// Additional headers sent with every request, e.g. for corporate proxies
+    #[serde(default)]
+    pub extra_headers: HashMap<String, String>,
+    /// Hosts that receive the `Authorization` header; unset sends it to every host
+    #[serde(default)]
+    pub auth_hosts: Option<Vec<String>>,
+    /// Consecutive failed requests that open the circuit breaker; 0 disables it
+    #[serde(default = "default_circuit_breaker_threshold")]
+    pub circuit_breaker_threshold: u32,
+    /// How long an open circuit fails fast before probing the API again
+    #[serde(default = "default_circuit_breaker_cooldown_seconds")]
+    pub circuit_breaker_cooldown_seconds: u64,
+    /// Largest response body read before a request is abandoned
+    #[serde(default = "default_max_response_bytes")]
+    pub max_response_bytes: usize,
+    /// Longest JSON-RPC line accepted from a client before it is rejected unparsed
+    #[serde(default = "default_max_request_bytes")]
+    pub max_request_bytes: usize,
+    /// Serve responses from JSON files in `fixtures_dir` instead of calling the API
+    #[serde(default)]
+    pub offline_mode: bool,
+    /// Directory of fixtures used by `offline_mode`, e.g. `markets.json`, `market_<id>.json`
+    #[serde(default = "default_fixtures_dir")]
+    pub fixtures_dir: String,
+}
+
+fn default_max_retry_delay_ms() -> u64 {
+    30_000
+}
+
+fn default_search_scan_limit() -> u32 {
+    500
+}
+
+fn default_scan_concurrency() -> usize {
+    4
+}
+
+fn default_max_limit() -> u32 {
+    500
+}
+
+fn default_batch_size() -> usize {
+    10
+}
+
+fn default_batch_delay_ms() -> u64 {
+    100
+}
+
+fn default_pool_max_idle_per_host() -> usize {
+    10
+}
+
+fn default_pool_idle_timeout_seconds() -> u64 {
+    30
+}
+
+fn default_user_agent() -> String {
+    format!("polymarket-mcp/{}", env!("CARGO_PKG_VERSION"))
+}
+
+fn default_completions() -> bool {
+    true
+}
+
+fn default_circuit_breaker_threshold() -> u32 {
+    5
+}
+
+fn default_circuit_breaker_cooldown_seconds() -> u64 {
+    30
+}
+
+fn default_max_response_bytes() -> usize {
+    32 * 1024 * 1024
+}
+
+fn default_max_request_bytes() -> usize {
+    4 * 1024 * 1024
+}
+
+fn default_fixtures_dir() -> String {
+    "fixtures".to_string()
 }
 
 impl std::fmt::Debug for ApiConfig {
@@ -39,6 +194,9 @@ impl std::fmt::Debug for ApiConfig {
             .field("base_url", &self.base_url)
             .field("api_key", &self.api_key.as_ref().map(|_| "[REDACTED]"))
             .field("rate_limit_per_second", &self.rate_limit_per_second)
+            .field("requests_per_second", &self.requests_per_second)
+            .field("auth_hosts", &self.auth_hosts)
+            .field("offline_mode", &self.offline_mode)
             .finish()
     }
 }
@@ -49,6 +207,111 @@ pub struct CacheConfig {
     pub ttl_seconds: u64,
     pub max_entries: usize,
     pub resource_cache_ttl_seconds: u64,
+    /// TTL for the `markets:trending` resource; falls back to `resource_cache_ttl_seconds`
+    #[serde(default)]
+    pub trending_ttl_seconds: Option<u64>,
+    /// TTL for the `markets:active` resource; falls back to `resource_cache_ttl_seconds`
+    #[serde(default)]
+    pub active_ttl_seconds: Option<u64>,
+    /// TTL for `market:<id>` resources; falls back to `resource_cache_ttl_seconds`
+    #[serde(default)]
+    pub market_ttl_seconds: Option<u64>,
+    /// How long `search_markets` results are reused for an identical query
+    #[serde(default = "default_search_ttl_seconds")]
+    pub search_ttl_seconds: u64,
+    /// How often expired cache entries are purged in the background
+    #[serde(default = "default_cleanup_interval_seconds")]
+    pub cleanup_interval_seconds: u64,
+    /// File the market caches are saved to on shutdown and restored from on startup
+    #[serde(default)]
+    pub persist_path: Option<String>,
+}
+
+fn default_cleanup_interval_seconds() -> u64 {
+    60
+}
+
+fn default_search_ttl_seconds() -> u64 {
+    30
+}
+
+/// Thresholds used to derive deterministic market signals
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AnalyticsConfig {
+    /// Liquidity (USD) at or above which a market is rated "medium"
+    pub liquidity_medium_threshold: f64,
+    /// Liquidity (USD) at or above which a market is rated "high"
+    pub liquidity_high_threshold: f64,
+    /// Number of active markets `market_overview` aggregates over
+    pub overview_sample_size: u32,
+    /// Weight of the 24h volume surge in notability scores
+    pub notability_volume_weight: f64,
+    /// Weight of (log-scaled) liquidity in notability scores
+    pub notability_liquidity_weight: f64,
+    /// Weight of closeness to the end date in notability scores
+    pub notability_time_weight: f64,
+}
+
+impl Default for AnalyticsConfig {
+    fn default() -> Self {
+        Self {
+            liquidity_medium_threshold: 10_000.0,
+            liquidity_high_threshold: 100_000.0,
+            overview_sample_size: 200,
+            notability_volume_weight: 0.5,
+            notability_liquidity_weight: 0.3,
+            notability_time_weight: 0.2,
+        }
+    }
+}
+
+/// Formatting of tool output
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OutputConfig {
+    /// Decimal places prices and probabilities are rounded to
+    pub price_decimals: u32,
+}
+
+impl Default for OutputConfig {
+    fn default() -> Self {
+        Self { price_decimals: 4 }
+    }
+}
+
+/// Which markets `get_active_markets` returns when the caller does not say
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DefaultsConfig {
+    /// Only return markets the API flags as active; `false` returns every market
+    pub active_only: bool,
+    /// Also return archived markets
+    pub include_archived: bool,
+}
+
+impl Default for DefaultsConfig {
+    fn default() -> Self {
+        Self {
+            active_only: true,
+            include_archived: false,
+        }
+    }
+}
+
+/// Most decimal places `output.price_decimals` may request; f64 noise starts beyond this
+pub const MAX_PRICE_DECIMALS: u32 = 12;
+
+/// Per-host base URLs; see [`Config::gamma_url`] for how unset hosts are resolved
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EndpointsConfig {
+    /// Markets, events and tags
+    pub gamma_url: Option<String>,
+    /// Order books and price history
+    pub clob_url: Option<String>,
+    /// Trades and positions
+    pub data_api_url: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -69,20 +332,49 @@ impl Default for Config {
                 description: "MCP server for Polymarket prediction market data".to_string(),
                 max_connections: Some(100),
                 timeout_seconds: 30,
+                prompts_dir: None,
+                allowed_origins: Vec::new(),
+                completions: default_completions(),
             },
             api: ApiConfig {
-                base_url: "https://gamma-api.polymarket.com".to_string(),
+                base_url: DEFAULT_GAMMA_URL.to_string(),
                 api_key: None,
                 timeout_seconds: 30,
                 max_retries: 3,
                 retry_delay_ms: 100,
-                rate_limit_per_second: Some(10),
+                max_retry_delay_ms: default_max_retry_delay_ms(),
+                rate_limit_per_second: None,
+                requests_per_second: 0,
+                search_scan_limit: default_search_scan_limit(),
+                scan_concurrency: default_scan_concurrency(),
+                max_limit: default_max_limit(),
+                batch_size: default_batch_size(),
+                batch_delay_ms: default_batch_delay_ms(),
+                pool_max_idle_per_host: default_pool_max_idle_per_host(),
+                pool_idle_timeout_seconds: default_pool_idle_timeout_seconds(),
+                http2_prior_knowledge: false,
+                proxy_url: None,
+                user_agent: default_user_agent(),
+                extra_headers: HashMap::new(),
+                auth_hosts: None,
+                circuit_breaker_threshold: default_circuit_breaker_threshold(),
+                circuit_breaker_cooldown_seconds: default_circuit_breaker_cooldown_seconds(),
+                max_response_bytes: default_max_response_bytes(),
+                max_request_bytes: default_max_request_bytes(),
+                offline_mode: false,
+                fixtures_dir: default_fixtures_dir(),
             },
             cache: CacheConfig {
                 enabled: true,
                 ttl_seconds: 60,
                 max_entries: 1000,
                 resource_cache_ttl_seconds: 300,
+                trending_ttl_seconds: None,
+                active_ttl_seconds: None,
+                market_ttl_seconds: None,
+                search_ttl_seconds: default_search_ttl_seconds(),
+                cleanup_interval_seconds: default_cleanup_interval_seconds(),
+                persist_path: None,
             },
             logging: LoggingConfig {
                 level: "info".to_string(),
@@ -91,6 +383,11 @@ impl Default for Config {
                 log_to_file: false,
                 log_file_path: None,
             },
+            analytics: AnalyticsConfig::default(),
+            endpoints: EndpointsConfig::default(),
+            output: OutputConfig::default(),
+            defaults: DefaultsConfig::default(),
+            watchlist: Vec::new(),
         }
     }
 }
@@ -98,6 +395,13 @@ impl Default for Config {
 impl Config {
     /// Loads configuration from default sources (environment variables and config files).
     ///
+    /// Sources are layered from lowest to highest precedence:
+    /// 1. Built-in defaults
+    /// 2. The first config file found in the default locations
+    /// 3. `POLYMARKET_*` environment variables (including those loaded from `.env`)
+    ///
+    /// Command-line flags are applied by the binary on top of the result.
+    ///
     /// # Errors
     ///
     /// Returns an error if:
@@ -111,7 +415,38 @@ impl Config {
             config = Self::merge_configs(config, file_config);
         }
 
-        config = Self::load_from_env(config)?;
+        config = Self::apply_env(config, |name| env::var(name).ok())?;
+        config.validate()?;
+
+        Ok(config)
+    }
+
+    /// Loads configuration from `config_path` with environment variables layered on top.
+    ///
+    /// Precedence matches [`Config::load`], with `config_path` in place of the default
+    /// file locations.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file is missing or unparseable, an environment variable
+    /// cannot be parsed, or the combined configuration is invalid.
+    pub fn load_with_file(config_path: &str) -> Result<Self> {
+        Self::load_with_file_and_env(config_path, |name| env::var(name).ok())
+    }
+
+    /// Like [`Config::load_with_file`], but looks variables up through `var` instead
+    /// of the process environment.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file is missing or unparseable, a variable cannot be
+    /// parsed, or the combined configuration is invalid.
+    pub fn load_with_file_and_env(
+        config_path: &str,
+        var: impl Fn(&str) -> Option<String>,
+    ) -> Result<Self> {
+        let config = Self::read_file(config_path)?;
+        let config = Self::apply_env(config, var)?;
         config.validate()?;
 
         Ok(config)
@@ -126,6 +461,15 @@ impl Config {
     /// - The config file cannot be parsed
     /// - The config contains invalid values
     pub fn load_from_file(config_path: &str) -> Result<Self> {
+        let config = Self::read_file(config_path)?;
+        config
+            .validate()
+            .context(format!("Invalid config in {config_path}"))?;
+
+        Ok(config)
+    }
+
+    fn read_file(config_path: &str) -> Result<Self> {
         if !Path::new(config_path).exists() {
             return Err(anyhow::anyhow!("Config file not found: {}", config_path));
         }
@@ -168,74 +512,254 @@ impl Config {
         Err(anyhow::anyhow!("No config file found"))
     }
 
-    fn load_from_env(mut config: Self) -> Result<Self> {
+    fn apply_env(mut config: Self, var: impl Fn(&str) -> Option<String>) -> Result<Self> {
         // Server configuration
-        if let Ok(val) = env::var("POLYMARKET_SERVER_NAME") {
+        if let Some(val) = var("POLYMARKET_SERVER_NAME") {
             config.server.name = val;
         }
-        if let Ok(val) = env::var("POLYMARKET_SERVER_MAX_CONNECTIONS") {
+        if let Some(val) = var("POLYMARKET_SERVER_DESCRIPTION") {
+            config.server.description = val;
+        }
+        if let Some(val) = var("POLYMARKET_SERVER_MAX_CONNECTIONS") {
             config.server.max_connections = Some(val.parse().context("Invalid max_connections")?);
         }
-        if let Ok(val) = env::var("POLYMARKET_SERVER_TIMEOUT") {
+        if let Some(val) = var("POLYMARKET_SERVER_TIMEOUT") {
             config.server.timeout_seconds = val.parse().context("Invalid server timeout")?;
         }
+        if let Some(val) = var("POLYMARKET_SERVER_PROMPTS_DIR") {
+            config.server.prompts_dir = Some(val);
+        }
+        if let Some(val) = var("POLYMARKET_SERVER_ALLOWED_ORIGINS") {
+            config.server.allowed_origins = val
+                .split(',')
+                .map(str::trim)
+                .filter(|origin| !origin.is_empty())
+                .map(str::to_string)
+                .collect();
+        }
+        if let Some(val) = var("POLYMARKET_SERVER_COMPLETIONS") {
+            config.server.completions = val.parse().context("Invalid completions")?;
+        }
 
         // API configuration
-        if let Ok(val) = env::var("POLYMARKET_API_BASE_URL") {
+        if let Some(val) = var("POLYMARKET_API_BASE_URL") {
             config.api.base_url = val;
         }
-        if let Ok(val) = env::var("POLYMARKET_API_KEY") {
+        if let Some(val) = var("POLYMARKET_API_KEY") {
             config.api.api_key = Some(val);
         }
-        if let Ok(val) = env::var("POLYMARKET_API_TIMEOUT") {
+        if let Some(val) = var("POLYMARKET_API_TIMEOUT") {
             config.api.timeout_seconds = val.parse().context("Invalid API timeout")?;
         }
-        if let Ok(val) = env::var("POLYMARKET_API_MAX_RETRIES") {
+        if let Some(val) = var("POLYMARKET_API_MAX_RETRIES") {
             config.api.max_retries = val.parse().context("Invalid max_retries")?;
         }
-        if let Ok(val) = env::var("POLYMARKET_API_RETRY_DELAY") {
+        if let Some(val) = var("POLYMARKET_API_RETRY_DELAY") {
             config.api.retry_delay_ms = val.parse().context("Invalid retry_delay")?;
         }
-        if let Ok(val) = env::var("POLYMARKET_API_RATE_LIMIT") {
+        if let Some(val) = var("POLYMARKET_API_MAX_RETRY_DELAY") {
+            config.api.max_retry_delay_ms = val.parse().context("Invalid max_retry_delay")?;
+        }
+        if let Some(val) = var("POLYMARKET_API_RATE_LIMIT") {
             config.api.rate_limit_per_second = Some(val.parse().context("Invalid rate_limit")?);
         }
+        if let Some(val) = var("POLYMARKET_API_REQUESTS_PER_SECOND") {
+            config.api.requests_per_second = val.parse().context("Invalid requests_per_second")?;
+        }
+        if let Some(val) = var("POLYMARKET_API_SEARCH_SCAN_LIMIT") {
+            config.api.search_scan_limit = val.parse().context("Invalid search_scan_limit")?;
+        }
+        if let Some(val) = var("POLYMARKET_API_SCAN_CONCURRENCY") {
+            config.api.scan_concurrency = val.parse().context("Invalid scan_concurrency")?;
+        }
+        if let Some(val) = var("POLYMARKET_API_POOL_MAX_IDLE_PER_HOST") {
+            config.api.pool_max_idle_per_host =
+                val.parse().context("Invalid pool_max_idle_per_host")?;
+        }
+        if let Some(val) = var("POLYMARKET_API_POOL_IDLE_TIMEOUT") {
+            config.api.pool_idle_timeout_seconds =
+                val.parse().context("Invalid pool_idle_timeout_seconds")?;
+        }
+        if let Some(val) = var("POLYMARKET_API_HTTP2_PRIOR_KNOWLEDGE") {
+            config.api.http2_prior_knowledge =
+                val.parse().context("Invalid http2_prior_knowledge")?;
+        }
+        if let Some(val) = var("POLYMARKET_API_MAX_LIMIT") {
+            config.api.max_limit = val.parse().context("Invalid max_limit")?;
+        }
+        if let Some(val) = var("POLYMARKET_API_BATCH_SIZE") {
+            config.api.batch_size = val.parse().context("Invalid batch_size")?;
+        }
+        if let Some(val) = var("POLYMARKET_API_BATCH_DELAY_MS") {
+            config.api.batch_delay_ms = val.parse().context("Invalid batch_delay_ms")?;
+        }
+        if let Some(val) = var("POLYMARKET_API_CIRCUIT_BREAKER_THRESHOLD") {
+            config.api.circuit_breaker_threshold =
+                val.parse().context("Invalid circuit_breaker_threshold")?;
+        }
+        if let Some(val) = var("POLYMARKET_API_CIRCUIT_BREAKER_COOLDOWN") {
+            config.api.circuit_breaker_cooldown_seconds =
+                val.parse().context("Invalid circuit_breaker_cooldown")?;
+        }
+        if let Some(val) = var("POLYMARKET_API_MAX_RESPONSE_BYTES") {
+            config.api.max_response_bytes = val.parse().context("Invalid max_response_bytes")?;
+        }
+        if let Some(val) = var("POLYMARKET_API_MAX_REQUEST_BYTES") {
+            config.api.max_request_bytes = val.parse().context("Invalid max_request_bytes")?;
+        }
+        if let Some(val) = var("POLYMARKET_API_OFFLINE_MODE") {
+            config.api.offline_mode = val.parse().context("Invalid offline_mode")?;
+        }
+        if let Some(val) = var("POLYMARKET_API_FIXTURES_DIR") {
+            config.api.fixtures_dir = val;
+        }
+        if let Some(val) = var("POLYMARKET_API_PROXY_URL") {
+            config.api.proxy_url = Some(val);
+        }
+        if let Some(val) = var("POLYMARKET_API_USER_AGENT") {
+            config.api.user_agent = val;
+        }
+        if let Some(val) = var("POLYMARKET_API_EXTRA_HEADERS") {
+            config.api.extra_headers = val
+                .split(',')
+                .filter(|pair| !pair.trim().is_empty())
+                .map(|pair| {
+                    pair.split_once('=')
+                        .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+                        .with_context(|| {
+                            format!("Invalid extra header '{pair}', expected Name=value")
+                        })
+                })
+                .collect::<Result<_>>()?;
+        }
+        if let Some(val) = var("POLYMARKET_API_AUTH_HOSTS") {
+            config.api.auth_hosts = Some(
+                val.split(',')
+                    .map(str::trim)
+                    .filter(|host| !host.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+            );
+        }
 
         // Cache configuration
-        if let Ok(val) = env::var("POLYMARKET_CACHE_ENABLED") {
+        if let Some(val) = var("POLYMARKET_CACHE_ENABLED") {
             config.cache.enabled = val.parse().context("Invalid cache_enabled")?;
         }
-        if let Ok(val) = env::var("POLYMARKET_CACHE_TTL") {
+        if let Some(val) = var("POLYMARKET_CACHE_TTL") {
             config.cache.ttl_seconds = val.parse().context("Invalid cache_ttl")?;
         }
-        if let Ok(val) = env::var("POLYMARKET_CACHE_MAX_ENTRIES") {
+        if let Some(val) = var("POLYMARKET_CACHE_MAX_ENTRIES") {
             config.cache.max_entries = val.parse().context("Invalid cache_max_entries")?;
         }
-        if let Ok(val) = env::var("POLYMARKET_RESOURCE_CACHE_TTL") {
+        if let Some(val) = var("POLYMARKET_RESOURCE_CACHE_TTL") {
             config.cache.resource_cache_ttl_seconds =
                 val.parse().context("Invalid resource_cache_ttl")?;
         }
+        if let Some(val) = var("POLYMARKET_TRENDING_CACHE_TTL") {
+            config.cache.trending_ttl_seconds =
+                Some(val.parse().context("Invalid trending_cache_ttl")?);
+        }
+        if let Some(val) = var("POLYMARKET_ACTIVE_CACHE_TTL") {
+            config.cache.active_ttl_seconds =
+                Some(val.parse().context("Invalid active_cache_ttl")?);
+        }
+        if let Some(val) = var("POLYMARKET_MARKET_CACHE_TTL") {
+            config.cache.market_ttl_seconds =
+                Some(val.parse().context("Invalid market_cache_ttl")?);
+        }
+        if let Some(val) = var("POLYMARKET_SEARCH_CACHE_TTL") {
+            config.cache.search_ttl_seconds = val.parse().context("Invalid search_cache_ttl")?;
+        }
+        if let Some(val) = var("POLYMARKET_CACHE_CLEANUP_INTERVAL") {
+            config.cache.cleanup_interval_seconds =
+                val.parse().context("Invalid cache_cleanup_interval")?;
+        }
+        if let Some(val) = var("POLYMARKET_CACHE_PERSIST_PATH") {
+            config.cache.persist_path = Some(val);
+        }
 
         // Logging configuration
-        if let Ok(val) = env::var("POLYMARKET_LOG_LEVEL") {
+        if let Some(val) = var("POLYMARKET_LOG_LEVEL") {
             config.logging.level = val;
         }
-        if let Ok(val) = env::var("POLYMARKET_LOG_FORMAT") {
+        if let Some(val) = var("POLYMARKET_LOG_FORMAT") {
             config.logging.format = val;
         }
-        if let Ok(val) = env::var("POLYMARKET_LOG_COLORS") {
+        if let Some(val) = var("POLYMARKET_LOG_COLORS") {
             config.logging.enable_colors = val.parse().context("Invalid log_colors")?;
         }
-        if let Ok(val) = env::var("POLYMARKET_LOG_TO_FILE") {
+        if let Some(val) = var("POLYMARKET_LOG_TO_FILE") {
             config.logging.log_to_file = val.parse().context("Invalid log_to_file")?;
         }
-        if let Ok(val) = env::var("POLYMARKET_LOG_FILE_PATH") {
+        if let Some(val) = var("POLYMARKET_LOG_FILE_PATH") {
             config.logging.log_file_path = Some(val);
         }
 
-        if let Ok(val) = env::var("RUST_LOG") {
+        if let Some(val) = var("RUST_LOG") {
             config.logging.level = val;
         }
 
+        // Analytics configuration
+        if let Some(val) = var("POLYMARKET_LIQUIDITY_MEDIUM_THRESHOLD") {
+            config.analytics.liquidity_medium_threshold =
+                val.parse().context("Invalid liquidity_medium_threshold")?;
+        }
+        if let Some(val) = var("POLYMARKET_LIQUIDITY_HIGH_THRESHOLD") {
+            config.analytics.liquidity_high_threshold =
+                val.parse().context("Invalid liquidity_high_threshold")?;
+        }
+        if let Some(val) = var("POLYMARKET_OVERVIEW_SAMPLE_SIZE") {
+            config.analytics.overview_sample_size =
+                val.parse().context("Invalid overview_sample_size")?;
+        }
+        if let Some(val) = var("POLYMARKET_NOTABILITY_VOLUME_WEIGHT") {
+            config.analytics.notability_volume_weight =
+                val.parse().context("Invalid notability_volume_weight")?;
+        }
+        if let Some(val) = var("POLYMARKET_NOTABILITY_LIQUIDITY_WEIGHT") {
+            config.analytics.notability_liquidity_weight =
+                val.parse().context("Invalid notability_liquidity_weight")?;
+        }
+        if let Some(val) = var("POLYMARKET_NOTABILITY_TIME_WEIGHT") {
+            config.analytics.notability_time_weight =
+                val.parse().context("Invalid notability_time_weight")?;
+        }
+
+        // Output configuration
+        if let Some(val) = var("POLYMARKET_OUTPUT_PRICE_DECIMALS") {
+            config.output.price_decimals = val.parse().context("Invalid price_decimals")?;
+        }
+
+        // Market listing defaults
+        if let Some(val) = var("POLYMARKET_DEFAULTS_ACTIVE_ONLY") {
+            config.defaults.active_only = val.parse().context("Invalid active_only")?;
+        }
+        if let Some(val) = var("POLYMARKET_DEFAULTS_INCLUDE_ARCHIVED") {
+            config.defaults.include_archived = val.parse().context("Invalid include_archived")?;
+        }
+
+        // Endpoint configuration
+        if let Some(val) = var("POLYMARKET_GAMMA_URL") {
+            config.endpoints.gamma_url = Some(val);
+        }
+        if let Some(val) = var("POLYMARKET_CLOB_URL") {
+            config.endpoints.clob_url = Some(val);
+        }
+        if let Some(val) = var("POLYMARKET_DATA_API_URL") {
+            config.endpoints.data_api_url = Some(val);
+        }
+
+        // Watchlist
+        if let Some(val) = var("POLYMARKET_WATCHLIST") {
+            config.watchlist = val
+                .split(',')
+                .map(str::trim)
+                .filter(|id| !id.is_empty())
+                .map(str::to_string)
+                .collect();
+        }
+
         Ok(config)
     }
 
@@ -243,29 +767,99 @@ impl Config {
         override_config
     }
 
-    fn validate(&self) -> Result<()> {
+    /// Rejects values that would otherwise surface as confusing runtime failures.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error naming the first offending field.
+    pub fn validate(&self) -> Result<()> {
         // Validate server configuration
         if self.server.name.is_empty() {
             return Err(anyhow::anyhow!("Server name cannot be empty"));
         }
 
         if self.server.timeout_seconds == 0 {
-            return Err(anyhow::anyhow!("Server timeout must be greater than 0"));
+            return Err(anyhow::anyhow!(
+                "Server timeout_seconds must be greater than 0"
+            ));
         }
 
         // Validate API configuration
         if self.api.base_url.is_empty() {
-            return Err(anyhow::anyhow!("API base URL cannot be empty"));
+            return Err(anyhow::anyhow!("API base_url cannot be empty"));
         }
 
         if !self.api.base_url.starts_with("http://") && !self.api.base_url.starts_with("https://") {
             return Err(anyhow::anyhow!(
-                "API base URL must start with http:// or https://"
+                "API base_url must start with http:// or https://"
             ));
         }
 
         if self.api.timeout_seconds == 0 {
-            return Err(anyhow::anyhow!("API timeout must be greater than 0"));
+            return Err(anyhow::anyhow!(
+                "API timeout_seconds must be greater than 0"
+            ));
+        }
+
+        // The retry loop runs `1..=max_retries`, so 0 would never send a request
+        if self.api.max_retries == 0 {
+            return Err(anyhow::anyhow!("API max_retries must be at least 1"));
+        }
+
+        // Backoff multiplies this delay, so 0 would retry and reconnect in a hot loop
+        if self.api.retry_delay_ms == 0 {
+            return Err(anyhow::anyhow!("API retry_delay_ms must be greater than 0"));
+        }
+
+        if self.api.max_retry_delay_ms < self.api.retry_delay_ms {
+            return Err(anyhow::anyhow!(
+                "API max_retry_delay_ms ({}) must not be less than retry_delay_ms ({})",
+                self.api.max_retry_delay_ms,
+                self.api.retry_delay_ms
+            ));
+        }
+
+        if self.api.max_limit == 0 {
+            return Err(anyhow::anyhow!("API max_limit must be greater than 0"));
+        }
+
+        if let Some(proxy_url) = &self.api.proxy_url {
+            let schemes = ["http://", "https://", "socks5://", "socks5h://"];
+            if !schemes.iter().any(|scheme| proxy_url.starts_with(scheme)) {
+                return Err(anyhow::anyhow!(
+                    "API proxy_url must start with one of: {}",
+                    schemes.join(", ")
+                ));
+            }
+        }
+
+        if self.api.max_response_bytes == 0 {
+            return Err(anyhow::anyhow!(
+                "API max_response_bytes must be greater than 0"
+            ));
+        }
+
+        if self.api.max_request_bytes == 0 {
+            return Err(anyhow::anyhow!(
+                "API max_request_bytes must be greater than 0"
+            ));
+        }
+
+        if self.api.offline_mode && !Path::new(&self.api.fixtures_dir).is_dir() {
+            return Err(anyhow::anyhow!(
+                "API fixtures_dir '{}' must be an existing directory when offline_mode is enabled",
+                self.api.fixtures_dir
+            ));
+        }
+
+        if self.api.batch_size == 0 {
+            return Err(anyhow::anyhow!("API batch_size must be greater than 0"));
+        }
+
+        if self.api.scan_concurrency == 0 {
+            return Err(anyhow::anyhow!(
+                "API scan_concurrency must be greater than 0"
+            ));
         }
 
         if self.api.max_retries > 10 {
@@ -278,7 +872,31 @@ impl Config {
         // Validate cache configuration
         if self.cache.ttl_seconds == 0 && self.cache.enabled {
             return Err(anyhow::anyhow!(
-                "Cache TTL must be greater than 0 when cache is enabled"
+                "Cache ttl_seconds must be greater than 0 when cache is enabled"
+            ));
+        }
+
+        if self.cache.resource_cache_ttl_seconds == 0 && self.cache.enabled {
+            return Err(anyhow::anyhow!(
+                "Cache resource_cache_ttl_seconds must be greater than 0 when cache is enabled"
+            ));
+        }
+
+        for (field, ttl) in [
+            ("trending_ttl_seconds", self.cache.trending_ttl_seconds),
+            ("active_ttl_seconds", self.cache.active_ttl_seconds),
+            ("market_ttl_seconds", self.cache.market_ttl_seconds),
+        ] {
+            if ttl == Some(0) && self.cache.enabled {
+                return Err(anyhow::anyhow!(
+                    "Cache {field} must be greater than 0 when cache is enabled"
+                ));
+            }
+        }
+
+        if self.cache.search_ttl_seconds == 0 && self.cache.enabled {
+            return Err(anyhow::anyhow!(
+                "Cache search_ttl_seconds must be greater than 0 when cache is enabled"
             ));
         }
 
@@ -288,20 +906,25 @@ impl Config {
             ));
         }
 
+        if self.cache.cleanup_interval_seconds == 0 && self.cache.enabled {
+            return Err(anyhow::anyhow!(
+                "Cache cleanup_interval_seconds must be greater than 0 when cache is enabled"
+            ));
+        }
+
         // Validate logging configuration
-        let valid_levels = ["trace", "debug", "info", "warn", "error"];
-        if !valid_levels.contains(&self.logging.level.as_str()) {
+        if !LOG_LEVELS.contains(&self.logging.level.as_str()) {
             return Err(anyhow::anyhow!(
-                "Invalid log level '{}'. Valid levels: {}",
+                "Invalid logging.level '{}'. Valid levels: {}",
                 self.logging.level,
-                valid_levels.join(", ")
+                LOG_LEVELS.join(", ")
             ));
         }
 
         let valid_formats = ["pretty", "json", "compact"];
         if !valid_formats.contains(&self.logging.format.as_str()) {
             return Err(anyhow::anyhow!(
-                "Invalid log format '{}'. Valid formats: {}",
+                "Invalid logging.format '{}'. Valid formats: {}",
                 self.logging.format,
                 valid_formats.join(", ")
             ));
@@ -309,13 +932,148 @@ impl Config {
 
         if self.logging.log_to_file && self.logging.log_file_path.is_none() {
             return Err(anyhow::anyhow!(
-                "Log file path must be specified when log_to_file is true"
+                "logging.log_file_path must be specified when log_to_file is true"
+            ));
+        }
+
+        // Validate endpoint configuration
+        for (name, url) in [
+            ("gamma_url", &self.endpoints.gamma_url),
+            ("clob_url", &self.endpoints.clob_url),
+            ("data_api_url", &self.endpoints.data_api_url),
+        ] {
+            if let Some(url) = url {
+                if !url.starts_with("http://") && !url.starts_with("https://") {
+                    return Err(anyhow::anyhow!(
+                        "Endpoint {} must start with http:// or https://",
+                        name
+                    ));
+                }
+            }
+        }
+
+        if self.output.price_decimals > MAX_PRICE_DECIMALS {
+            return Err(anyhow::anyhow!(
+                "output.price_decimals must be at most {MAX_PRICE_DECIMALS}, got {}",
+                self.output.price_decimals
+            ));
+        }
+
+        // Validate analytics configuration
+        if self.analytics.overview_sample_size == 0 {
+            return Err(anyhow::anyhow!(
+                "analytics.overview_sample_size must be greater than 0"
+            ));
+        }
+
+        if self.analytics.liquidity_medium_threshold > self.analytics.liquidity_high_threshold {
+            return Err(anyhow::anyhow!(
+                "liquidity_medium_threshold ({}) must not exceed liquidity_high_threshold ({})",
+                self.analytics.liquidity_medium_threshold,
+                self.analytics.liquidity_high_threshold
+            ));
+        }
+
+        let weights = [
+            self.analytics.notability_volume_weight,
+            self.analytics.notability_liquidity_weight,
+            self.analytics.notability_time_weight,
+        ];
+        if weights.iter().any(|w| !w.is_finite() || *w < 0.0) || weights.iter().sum::<f64>() <= 0.0
+        {
+            return Err(anyhow::anyhow!(
+                "analytics notability weights must be non-negative with a positive sum"
             ));
         }
 
         Ok(())
     }
 
+    /// Merges a freshly loaded `new` config into this running one for a live reload.
+    ///
+    /// Only these fields take effect without a restart:
+    /// - `cache.ttl_seconds`, `cache.search_ttl_seconds` and the resource TTLs
+    ///   (`cache.resource_cache_ttl_seconds` and its per-type overrides)
+    /// - `logging.level`
+    /// - `api.requests_per_second` (and its deprecated alias `api.rate_limit_per_second`)
+    ///
+    /// Everything else (hosts, API key, timeouts, pool and cache sizes, ...) is baked
+    /// into the HTTP client or background tasks at startup, so it keeps its current
+    /// value. Returns the merged config and the top-level sections of `new` that
+    /// contained such restart-only changes.
+    #[must_use]
+    pub fn with_reloadable_from(&self, new: &Config) -> (Config, Vec<&'static str>) {
+        let mut merged = self.clone();
+        merged.cache.ttl_seconds = new.cache.ttl_seconds;
+        merged.cache.resource_cache_ttl_seconds = new.cache.resource_cache_ttl_seconds;
+        merged.cache.trending_ttl_seconds = new.cache.trending_ttl_seconds;
+        merged.cache.active_ttl_seconds = new.cache.active_ttl_seconds;
+        merged.cache.market_ttl_seconds = new.cache.market_ttl_seconds;
+        merged.cache.search_ttl_seconds = new.cache.search_ttl_seconds;
+        merged.logging.level.clone_from(&new.logging.level);
+        merged.api.requests_per_second = new.api.requests_per_second;
+        merged.api.rate_limit_per_second = new.api.rate_limit_per_second;
+
+        let sections = |config: &Config| {
+            [
+                ("server", serde_json::to_value(&config.server)),
+                ("api", serde_json::to_value(&config.api)),
+                ("cache", serde_json::to_value(&config.cache)),
+                ("logging", serde_json::to_value(&config.logging)),
+                ("analytics", serde_json::to_value(&config.analytics)),
+                ("endpoints", serde_json::to_value(&config.endpoints)),
+                ("output", serde_json::to_value(&config.output)),
+                ("defaults", serde_json::to_value(&config.defaults)),
+                ("watchlist", serde_json::to_value(&config.watchlist)),
+            ]
+            .map(|(name, value)| (name, value.ok()))
+        };
+        let ignored = sections(&merged)
+            .into_iter()
+            .zip(sections(new))
+            .filter(|((_, merged), (_, new))| merged != new)
+            .map(|((name, _), _)| name)
+            .collect();
+
+        (merged, ignored)
+    }
+
+    /// Base URL for Gamma market, event and tag requests.
+    ///
+    /// Falls back to `api.base_url`, which predates per-host configuration.
+    #[must_use]
+    pub fn gamma_url(&self) -> &str {
+        self.endpoints
+            .gamma_url
+            .as_deref()
+            .unwrap_or(&self.api.base_url)
+    }
+
+    /// Base URL for CLOB order book and price history requests.
+    ///
+    /// When unset, a customized `api.base_url` (e.g. a proxy in front of every host) is
+    /// used; otherwise the public CLOB host.
+    #[must_use]
+    pub fn clob_url(&self) -> &str {
+        self.endpoints
+            .clob_url
+            .as_deref()
+            .unwrap_or_else(|| self.custom_base_url().unwrap_or(DEFAULT_CLOB_URL))
+    }
+
+    /// Base URL for data API trade and position requests, resolved like [`Self::clob_url`].
+    #[must_use]
+    pub fn data_api_url(&self) -> &str {
+        self.endpoints
+            .data_api_url
+            .as_deref()
+            .unwrap_or_else(|| self.custom_base_url().unwrap_or(DEFAULT_DATA_API_URL))
+    }
+
+    fn custom_base_url(&self) -> Option<&str> {
+        (self.api.base_url != DEFAULT_GAMMA_URL).then_some(self.api.base_url.as_str())
+    }
+
     #[must_use]
     pub fn api_timeout(&self) -> Duration {
         Duration::from_secs(self.api.timeout_seconds)
@@ -336,8 +1094,61 @@ impl Config {
         Duration::from_secs(self.cache.resource_cache_ttl_seconds)
     }
 
+    /// TTL for the resource at `uri`: its per-type override if one is set, otherwise
+    /// [`Self::resource_cache_ttl`].
+    #[must_use]
+    pub fn resource_ttl_for(&self, uri: &str) -> Duration {
+        let specific = match uri {
+            "markets:trending" => self.cache.trending_ttl_seconds,
+            "markets:active" => self.cache.active_ttl_seconds,
+            _ if uri.starts_with("market:") => self.cache.market_ttl_seconds,
+            _ => None,
+        };
+        specific.map_or_else(|| self.resource_cache_ttl(), Duration::from_secs)
+    }
+
+    #[must_use]
+    pub fn search_cache_ttl(&self) -> Duration {
+        Duration::from_secs(self.cache.search_ttl_seconds)
+    }
+
+    #[must_use]
+    pub fn cache_cleanup_interval(&self) -> Duration {
+        Duration::from_secs(self.cache.cleanup_interval_seconds)
+    }
+
+    #[must_use]
+    pub fn pool_idle_timeout(&self) -> Duration {
+        Duration::from_secs(self.api.pool_idle_timeout_seconds)
+    }
+
+    /// Outgoing request rate limit, falling back to the deprecated
+    /// `api.rate_limit_per_second` when `api.requests_per_second` is 0.
+    #[must_use]
+    pub fn requests_per_second(&self) -> u32 {
+        match self.api.requests_per_second {
+            0 => self.api.rate_limit_per_second.unwrap_or(0),
+            rate => rate,
+        }
+    }
+
     #[must_use]
     pub fn retry_delay(&self) -> Duration {
         Duration::from_millis(self.api.retry_delay_ms)
     }
+
+    #[must_use]
+    pub fn circuit_breaker_cooldown(&self) -> Duration {
+        Duration::from_secs(self.api.circuit_breaker_cooldown_seconds)
+    }
+
+    #[must_use]
+    pub fn batch_delay(&self) -> Duration {
+        Duration::from_millis(self.api.batch_delay_ms)
+    }
+
+    #[must_use]
+    pub fn max_retry_delay(&self) -> Duration {
+        Duration::from_millis(self.api.max_retry_delay_ms)
+    }
 }
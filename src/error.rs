@@ -1,6 +1,5 @@
 use serde::{Deserialize, Serialize};
 use std::fmt;
-use tracing::error;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RequestId(pub String);
@@ -24,23 +23,58 @@ impl fmt::Display for RequestId {
     }
 }
 
-#[derive(Debug, thiserror::Error)]
+/// How hard the client tried before giving up on an upstream request
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetryStats {
+    pub attempts: u32,
+    /// Time from the first attempt until giving up, including backoff
+    pub total_elapsed_ms: u64,
+}
+
+#[derive(Debug, Clone, thiserror::Error)]
 pub enum PolymarketError {
     #[error("API request failed: {message} (request_id: {request_id})")]
     Api {
         message: String,
         status_code: Option<u16>,
         request_id: RequestId,
+        /// Set once the retry loop gives up on the request
+        retries: Option<RetryStats>,
     },
 
+    /// The API answered 404 for the requested resource
+    #[error("{resource} not found")]
+    NotFound { resource: String },
+
     #[error("Network error: {message}")]
-    Network { message: String },
+    Network {
+        message: String,
+        /// Set once the retry loop gives up on the request
+        retries: Option<RetryStats>,
+    },
 
     #[error("Deserialization error: {message}")]
     Deserialization { message: String },
 
     #[error("Configuration error: {message}")]
     Config { message: String },
+
+    /// A tool or prompt argument is missing or malformed; `reason` reads on from
+    /// the field name, e.g. "argument is required"
+    #[error("{field} {reason}")]
+    InvalidArgument { field: String, reason: String },
+
+    /// The client asked for a resource or prompt this server doesn't provide
+    #[error("Resource error: {message}")]
+    Resource { message: String },
+
+    /// A failure inside the server itself rather than upstream
+    #[error("Internal error: {message}")]
+    Internal { message: String },
+
+    /// Reading or writing a local file failed
+    #[error("I/O error on {path}: {message}")]
+    Io { path: String, message: String },
 }
 
 impl PolymarketError {
@@ -49,12 +83,20 @@ impl PolymarketError {
             message: message.into(),
             status_code,
             request_id: RequestId::new(),
+            retries: None,
+        }
+    }
+
+    pub fn not_found(resource: impl Into<String>) -> Self {
+        Self::NotFound {
+            resource: resource.into(),
         }
     }
 
     pub fn network_error(message: impl Into<String>) -> Self {
         Self::Network {
             message: message.into(),
+            retries: None,
         }
     }
 
@@ -69,6 +111,95 @@ impl PolymarketError {
             message: message.into(),
         }
     }
+
+    pub fn invalid_argument(field: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self::InvalidArgument {
+            field: field.into(),
+            reason: reason.into(),
+        }
+    }
+
+    pub fn resource_error(message: impl Into<String>) -> Self {
+        Self::Resource {
+            message: message.into(),
+        }
+    }
+
+    pub fn internal_error(message: impl Into<String>) -> Self {
+        Self::Internal {
+            message: message.into(),
+        }
+    }
+
+    pub fn io_error(path: impl Into<String>, error: &std::io::Error) -> Self {
+        Self::Io {
+            path: path.into(),
+            message: error.to_string(),
+        }
+    }
+
+    /// Records how many attempts were made over how long. Only upstream API and
+    /// network errors carry retry stats; other errors are returned unchanged.
+    #[must_use]
+    pub fn with_retry_stats(mut self, attempts: u32, elapsed: std::time::Duration) -> Self {
+        if let Self::Api { retries, .. } | Self::Network { retries, .. } = &mut self {
+            *retries = Some(RetryStats {
+                attempts,
+                total_elapsed_ms: u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX),
+            });
+        }
+        self
+    }
+
+    /// Stable snake_case name of the variant, reported to clients as `error_type`
+    #[must_use]
+    pub fn error_type(&self) -> &'static str {
+        match self {
+            Self::Api { .. } => "api_error",
+            Self::NotFound { .. } => "not_found",
+            Self::Network { .. } => "network_error",
+            Self::Deserialization { .. } => "deserialization_error",
+            Self::Config { .. } => "config_error",
+            Self::InvalidArgument { .. } => "invalid_argument",
+            Self::Resource { .. } => "resource_error",
+            Self::Internal { .. } => "internal_error",
+            Self::Io { .. } => "io_error",
+        }
+    }
+
+    /// HTTP status of the upstream response, when there was one
+    #[must_use]
+    pub fn status_code(&self) -> Option<u16> {
+        match self {
+            Self::Api { status_code, .. } => *status_code,
+            Self::NotFound { .. } => Some(404),
+            _ => None,
+        }
+    }
+
+    /// Retry stats of a failed upstream request, when it went through the retry loop
+    #[must_use]
+    pub fn retry_stats(&self) -> Option<RetryStats> {
+        match self {
+            Self::Api { retries, .. } | Self::Network { retries, .. } => *retries,
+            _ => None,
+        }
+    }
+
+    /// Id of the failed upstream request, for errors that carry one
+    #[must_use]
+    pub fn request_id(&self) -> Option<&RequestId> {
+        match self {
+            Self::Api { request_id, .. } => Some(request_id),
+            _ => None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for PolymarketError {
+    fn from(error: serde_json::Error) -> Self {
+        Self::internal_error(format!("JSON serialization failed: {error}"))
+    }
 }
 
 pub type Result<T> = std::result::Result<T, PolymarketError>;
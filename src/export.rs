@@ -0,0 +1,75 @@
+//! Flat-file renderings of market lists for spreadsheet users.
+
+use crate::models::Market;
+
+/// Columns written by [`markets_to_csv`], in order
+pub const CSV_COLUMNS: &[&str] = &[
+    "id",
+    "slug",
+    "question",
+    "category",
+    "liquidity",
+    "volume",
+    "volume_24hr",
+    "end_date",
+    "favorite_outcome",
+    "favorite_price",
+];
+
+/// Renders markets as RFC 4180 CSV with a header row and CRLF line endings.
+///
+/// Missing values are left empty.
+#[must_use]
+pub fn markets_to_csv(markets: &[Market]) -> String {
+    let mut csv = CSV_COLUMNS.join(",");
+    csv.push_str("\r\n");
+
+    for market in markets {
+        let favorite = market.favorite_outcome();
+        let row = [
+            csv_field(&market.id),
+            csv_field(&market.slug),
+            csv_field(&market.question),
+            csv_field(market.category.as_deref().unwrap_or_default()),
+            market.liquidity.to_string(),
+            market.volume.to_string(),
+            market
+                .volume_24hr
+                .map(|v| v.to_string())
+                .unwrap_or_default(),
+            csv_field(&market.end_date),
+            favorite
+                .map(|(outcome, _)| csv_field(outcome))
+                .unwrap_or_default(),
+            favorite
+                .map(|(_, price)| price.to_string())
+                .unwrap_or_default(),
+        ];
+        csv.push_str(&row.join(","));
+        csv.push_str("\r\n");
+    }
+
+    csv
+}
+
+/// Quotes a field when it contains a delimiter, quote or line break, doubling any quotes.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_field_escaping() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("Yes, or no?"), "\"Yes, or no?\"");
+        assert_eq!(csv_field("The \"big\" one"), "\"The \"\"big\"\" one\"");
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+    }
+}
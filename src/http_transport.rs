@@ -0,0 +1,604 @@
+//! Streamable HTTP transport: JSON-RPC over POST with Server-Sent Events for notifications.
+//!
+//! Clients POST requests to `/mcp` and receive the JSON-RPC response as the HTTP body.
+//! The first POST opens a session whose id is returned in the `Mcp-Session-Id`
+//! header; later requests echo that header. A GET on `/mcp` with the session header
+//! opens an SSE stream carrying the session's notifications (e.g. resource updates),
+//! and DELETE ends the session. A session also ends when its SSE stream closes, or
+//! after [`SESSION_IDLE_TTL`] without requests if it never opened one.
+//!
+//! Request bodies are capped at `api.max_request_bytes`, and requests carrying an
+//! `Origin` header are only accepted from loopback origins or `server.allowed_origins`,
+//! which keeps web pages from reaching a local server through DNS rebinding.
+//!
+//! `GET /metrics` serves the client's [`Metrics`](polymarket_mcp::Metrics) in Prometheus
+//! text format for scraping.
+
+use crate::{handle_line, PolymarketMcpServer, Session, ACCEPT_ERROR_BACKOFF, NOTIFICATION_BUFFER};
+use anyhow::Result;
+use futures::stream;
+use http_body_util::{combinators::BoxBody, BodyExt, Full, LengthLimitError, Limited, StreamBody};
+use hyper::body::{Bytes, Frame, Incoming};
+use hyper::header::{HeaderValue, CONTENT_TYPE, ORIGIN};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Method, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::net::TcpListener;
+use tokio::sync::{mpsc, Mutex};
+
+const MCP_PATH: &str = "/mcp";
+const METRICS_PATH: &str = "/metrics";
+const SESSION_HEADER: &str = "mcp-session-id";
+
+/// Sessions without an event stream are dropped after this long without a request
+const SESSION_IDLE_TTL: Duration = Duration::from_secs(30 * 60);
+
+/// How often idle sessions are looked for
+const SESSION_REAP_INTERVAL: Duration = Duration::from_secs(60);
+
+type Body = BoxBody<Bytes, Infallible>;
+
+/// A session opened over HTTP. The notification receiver is handed to the first SSE
+/// stream opened for the session.
+struct HttpSession {
+    session: Session,
+    notifications: Option<mpsc::Receiver<Value>>,
+    last_active: Instant,
+}
+
+/// Sessions keyed by the id handed out in the `Mcp-Session-Id` header.
+type HttpSessions = Arc<Mutex<HashMap<String, HttpSession>>>;
+
+/// Accepts HTTP connections and serves the MCP endpoint on each one. Failing to
+/// accept one connection is logged and does not stop the server.
+pub(crate) async fn serve_http(
+    server: Arc<PolymarketMcpServer>,
+    listener: TcpListener,
+) -> Result<()> {
+    let sessions = HttpSessions::default();
+    let mut reap = tokio::time::interval(SESSION_REAP_INTERVAL);
+
+    loop {
+        let (stream, peer) = tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok(accepted) => accepted,
+                Err(e) => {
+                    tracing::warn!("Failed to accept HTTP connection: {}", e);
+                    tokio::time::sleep(ACCEPT_ERROR_BACKOFF).await;
+                    continue;
+                }
+            },
+            _ = reap.tick() => {
+                reap_idle_sessions(&server, &sessions, SESSION_IDLE_TTL).await;
+                continue;
+            }
+        };
+        tracing::debug!("Accepted HTTP connection from {}", peer);
+
+        let server = server.clone();
+        let sessions = sessions.clone();
+        tokio::spawn(async move {
+            let service = service_fn(move |request| {
+                let server = server.clone();
+                let sessions = sessions.clone();
+                async move { Ok::<_, Infallible>(route(&server, &sessions, request).await) }
+            });
+            if let Err(e) = http1::Builder::new()
+                .serve_connection(TokioIo::new(stream), service)
+                .await
+            {
+                tracing::debug!("HTTP connection from {} ended with error: {}", peer, e);
+            }
+        });
+    }
+}
+
+async fn route(
+    server: &Arc<PolymarketMcpServer>,
+    sessions: &HttpSessions,
+    request: Request<Incoming>,
+) -> Response<Body> {
+    let origin = request
+        .headers()
+        .get(ORIGIN)
+        .map(|value| value.to_str().unwrap_or_default());
+    if !origin_allowed(origin, &server.config().server.allowed_origins) {
+        tracing::warn!("Rejected HTTP request from origin {:?}", origin);
+        return text_response(StatusCode::FORBIDDEN, "Origin not allowed");
+    }
+
+    if request.uri().path() == METRICS_PATH && request.method() == Method::GET {
+        return metrics_response(server).await;
+    }
+    if request.uri().path() != MCP_PATH {
+        return text_response(StatusCode::NOT_FOUND, "Not found");
+    }
+
+    let session_id = request
+        .headers()
+        .get(SESSION_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+
+    match *request.method() {
+        Method::POST => handle_post(server, sessions, session_id, request).await,
+        Method::GET => handle_sse(server, sessions, session_id).await,
+        Method::DELETE => handle_delete(server, sessions, session_id).await,
+        _ => text_response(StatusCode::METHOD_NOT_ALLOWED, "Method not allowed"),
+    }
+}
+
+/// Dispatches one JSON-RPC message exactly as the stdio transport would.
+async fn handle_post(
+    server: &Arc<PolymarketMcpServer>,
+    sessions: &HttpSessions,
+    session_id: Option<String>,
+    request: Request<Incoming>,
+) -> Response<Body> {
+    let max_bytes = server.config().api.max_request_bytes;
+    let body = match Limited::new(request.into_body(), max_bytes).collect().await {
+        Ok(body) => body.to_bytes(),
+        Err(e) if e.is::<LengthLimitError>() => {
+            return text_response(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                &format!("Request body exceeds {max_bytes} bytes"),
+            );
+        }
+        Err(e) => {
+            return text_response(StatusCode::BAD_REQUEST, &format!("Unreadable body: {e}"));
+        }
+    };
+    let Ok(body) = std::str::from_utf8(&body) else {
+        return text_response(StatusCode::BAD_REQUEST, "Body must be UTF-8");
+    };
+
+    let mut sessions_guard = sessions.lock().await;
+    let session_id = match session_id {
+        Some(id) => match sessions_guard.get_mut(&id) {
+            Some(session) => {
+                session.last_active = Instant::now();
+                id
+            }
+            None => return text_response(StatusCode::NOT_FOUND, "Unknown session"),
+        },
+        None => {
+            let (notifier, notifications) = mpsc::channel(NOTIFICATION_BUFFER);
+            let id = uuid::Uuid::new_v4().to_string();
+            sessions_guard.insert(
+                id.clone(),
+                HttpSession {
+                    session: Session::new(notifier),
+                    notifications: Some(notifications),
+                    last_active: Instant::now(),
+                },
+            );
+            id
+        }
+    };
+    // Sessions are cheap handles; clone out so the lock isn't held across the dispatch
+    let session = sessions_guard[&session_id].session.clone();
+    drop(sessions_guard);
+
+    let mut response = match handle_line(server, &session, body).await {
+        Some(message) => Response::new(full(message.to_string())),
+        None => {
+            let mut response = Response::new(full(""));
+            *response.status_mut() = StatusCode::ACCEPTED;
+            response
+        }
+    };
+    response
+        .headers_mut()
+        .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    if let Ok(value) = HeaderValue::from_str(&session_id) {
+        response.headers_mut().insert(SESSION_HEADER, value);
+    }
+    response
+}
+
+/// Opens the Server-Sent Events stream for a session's notifications.
+///
+/// The session ends when the stream is dropped, i.e. when the client disconnects.
+async fn handle_sse(
+    server: &Arc<PolymarketMcpServer>,
+    sessions: &HttpSessions,
+    session_id: Option<String>,
+) -> Response<Body> {
+    let Some(session_id) = session_id else {
+        return text_response(StatusCode::BAD_REQUEST, "Missing Mcp-Session-Id header");
+    };
+    let notifications = match sessions.lock().await.get_mut(&session_id) {
+        Some(session) => {
+            session.last_active = Instant::now();
+            session.notifications.take()
+        }
+        None => return text_response(StatusCode::NOT_FOUND, "Unknown session"),
+    };
+    let Some(notifications) = notifications else {
+        return text_response(StatusCode::CONFLICT, "Session already has an event stream");
+    };
+
+    let guard = StreamGuard {
+        server: server.clone(),
+        sessions: sessions.clone(),
+        session_id,
+    };
+    let events = stream::unfold(
+        (notifications, guard),
+        |(mut notifications, guard)| async move {
+            let message = notifications.recv().await?;
+            let event = format!("event: message\ndata: {message}\n\n");
+            Some((Ok(Frame::data(Bytes::from(event))), (notifications, guard)))
+        },
+    );
+
+    let mut response = Response::new(BodyExt::boxed(StreamBody::new(events)));
+    let headers = response.headers_mut();
+    headers.insert(CONTENT_TYPE, HeaderValue::from_static("text/event-stream"));
+    headers.insert("cache-control", HeaderValue::from_static("no-cache"));
+    response
+}
+
+async fn handle_delete(
+    server: &Arc<PolymarketMcpServer>,
+    sessions: &HttpSessions,
+    session_id: Option<String>,
+) -> Response<Body> {
+    let Some(session_id) = session_id else {
+        return text_response(StatusCode::BAD_REQUEST, "Missing Mcp-Session-Id header");
+    };
+    if remove_session(server, sessions, &session_id).await {
+        let mut response = Response::new(full(""));
+        *response.status_mut() = StatusCode::NO_CONTENT;
+        response
+    } else {
+        text_response(StatusCode::NOT_FOUND, "Unknown session")
+    }
+}
+
+/// Removes a session and its subscriptions, returning whether it existed.
+async fn remove_session(
+    server: &Arc<PolymarketMcpServer>,
+    sessions: &HttpSessions,
+    session_id: &str,
+) -> bool {
+    let removed = sessions.lock().await.remove(session_id);
+    if let Some(http_session) = &removed {
+        server.end_session(&http_session.session).await;
+    }
+    removed.is_some()
+}
+
+/// Drops sessions that never opened an event stream and have been idle for `ttl`,
+/// returning how many were removed. Sessions with a stream end when it closes.
+async fn reap_idle_sessions(
+    server: &Arc<PolymarketMcpServer>,
+    sessions: &HttpSessions,
+    ttl: Duration,
+) -> usize {
+    let expired: Vec<HttpSession> = {
+        let mut sessions = sessions.lock().await;
+        let ids: Vec<String> = sessions
+            .iter()
+            .filter(|(_, session)| {
+                session.notifications.is_some() && session.last_active.elapsed() >= ttl
+            })
+            .map(|(id, _)| id.clone())
+            .collect();
+        ids.iter().filter_map(|id| sessions.remove(id)).collect()
+    };
+    for http_session in &expired {
+        server.end_session(&http_session.session).await;
+    }
+    if !expired.is_empty() {
+        tracing::debug!("Dropped {} idle HTTP sessions", expired.len());
+    }
+    expired.len()
+}
+
+/// Held by an SSE stream; ends the stream's session once the stream is dropped.
+struct StreamGuard {
+    server: Arc<PolymarketMcpServer>,
+    sessions: HttpSessions,
+    session_id: String,
+}
+
+impl Drop for StreamGuard {
+    fn drop(&mut self) {
+        let server = self.server.clone();
+        let sessions = self.sessions.clone();
+        let session_id = std::mem::take(&mut self.session_id);
+        // No runtime means the process is shutting down and the sessions go with it
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            runtime.spawn(async move {
+                if remove_session(&server, &sessions, &session_id).await {
+                    tracing::debug!("Event stream closed; ended session {}", session_id);
+                }
+            });
+        }
+    }
+}
+
+/// Whether a request with this `Origin` header may proceed.
+///
+/// Requests without the header come from non-browser clients and are allowed;
+/// browsers are limited to loopback origins and the configured `allowed`.
+fn origin_allowed(origin: Option<&str>, allowed: &[String]) -> bool {
+    let Some(origin) = origin else {
+        return true;
+    };
+    if allowed
+        .iter()
+        .any(|allowed| allowed.trim_end_matches('/') == origin)
+    {
+        return true;
+    }
+    reqwest::Url::parse(origin)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+        .is_some_and(|host| matches!(host.as_str(), "localhost" | "127.0.0.1" | "[::1]"))
+}
+
+async fn metrics_response(server: &Arc<PolymarketMcpServer>) -> Response<Body> {
+    let metrics = server.metrics_snapshot().await;
+    let mut response = Response::new(full(metrics.to_prometheus()));
+    response.headers_mut().insert(
+        CONTENT_TYPE,
+        HeaderValue::from_static("text/plain; version=0.0.4"),
+    );
+    response
+}
+
+fn full(body: impl Into<Bytes>) -> Body {
+    BodyExt::boxed(Full::new(body.into()))
+}
+
+fn text_response(status: StatusCode, message: &str) -> Response<Body> {
+    let mut response = Response::new(full(message.to_string()));
+    *response.status_mut() = status;
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use polymarket_mcp::Config;
+    use serde_json::json;
+
+    fn test_config() -> Config {
+        let mut config = Config::default();
+        config.api.base_url = "http://127.0.0.1:9".to_string();
+        config.cache.enabled = false;
+        config
+    }
+
+    async fn spawn_server() -> (Arc<PolymarketMcpServer>, String) {
+        spawn_server_with(test_config()).await
+    }
+
+    async fn spawn_server_with(config: Config) -> (Arc<PolymarketMcpServer>, String) {
+        let server = Arc::new(PolymarketMcpServer::with_config(config).unwrap());
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}{MCP_PATH}", listener.local_addr().unwrap());
+        tokio::spawn(serve_http(server.clone(), listener));
+        (server, url)
+    }
+
+    #[tokio::test]
+    async fn test_post_and_sse_round_trip() {
+        let (server, url) = spawn_server().await;
+        let http = reqwest::Client::new();
+
+        let response = http
+            .post(&url)
+            .body(json!({"jsonrpc": "2.0", "id": 1, "method": "tools/list"}).to_string())
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let session_id = response.headers()[SESSION_HEADER]
+            .to_str()
+            .unwrap()
+            .to_string();
+        let body: Value = response.json().await.unwrap();
+        assert_eq!(body["id"], 1);
+        assert!(body["result"]["tools"].is_array());
+
+        let response = http
+            .post(&url)
+            .header(SESSION_HEADER, &session_id)
+            .body(
+                json!({
+                    "jsonrpc": "2.0",
+                    "id": 2,
+                    "method": "resources/subscribe",
+                    "params": {"uri": "markets:active"}
+                })
+                .to_string(),
+            )
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.headers()[SESSION_HEADER], session_id.as_str());
+
+        let mut events = http
+            .get(&url)
+            .header(SESSION_HEADER, &session_id)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(events.headers()[CONTENT_TYPE], "text/event-stream");
+
+        server.notify_resource_updated("markets:active").await;
+        let chunk = tokio::time::timeout(Duration::from_secs(5), events.chunk())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        let chunk = String::from_utf8(chunk.to_vec()).unwrap();
+        assert!(chunk.starts_with("event: message\ndata: "));
+        assert!(chunk.contains("notifications/resources/updated"));
+
+        let response = http
+            .delete(&url)
+            .header(SESSION_HEADER, &session_id)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert!(server.subscriptions.read().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_metrics_endpoint_serves_prometheus_text() {
+        let (_server, url) = spawn_server().await;
+        let url = url.replace(MCP_PATH, METRICS_PATH);
+
+        let response = reqwest::get(&url).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers()[CONTENT_TYPE]
+            .to_str()
+            .unwrap()
+            .starts_with("text/plain"));
+        let body = response.text().await.unwrap();
+        assert!(body.contains("# TYPE polymarket_api_requests_total counter"));
+    }
+
+    #[tokio::test]
+    async fn test_notification_is_accepted_and_unknown_session_rejected() {
+        let (_server, url) = spawn_server().await;
+        let http = reqwest::Client::new();
+
+        let response = http
+            .post(&url)
+            .body(json!({"jsonrpc": "2.0", "method": "notifications/initialized"}).to_string())
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+
+        let response = http
+            .get(&url)
+            .header(SESSION_HEADER, "missing")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_foreign_origin_and_oversized_body() {
+        let mut config = test_config();
+        config.api.max_request_bytes = 256;
+        config.server.allowed_origins = vec!["https://app.example.com".to_string()];
+        let (_server, url) = spawn_server_with(config).await;
+        let http = reqwest::Client::new();
+        let request = json!({"jsonrpc": "2.0", "id": 1, "method": "tools/list"}).to_string();
+
+        for (origin, status) in [
+            ("https://evil.example.com", StatusCode::FORBIDDEN),
+            ("null", StatusCode::FORBIDDEN),
+            ("https://app.example.com", StatusCode::OK),
+            ("http://localhost:5173", StatusCode::OK),
+        ] {
+            let response = http
+                .post(&url)
+                .header(ORIGIN, origin)
+                .body(request.clone())
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), status, "{origin}");
+        }
+
+        let response = http.post(&url).body("x".repeat(1024)).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn test_closing_event_stream_ends_session() {
+        let (server, url) = spawn_server().await;
+        let http = reqwest::Client::new();
+
+        let response = http
+            .post(&url)
+            .body(
+                json!({
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "method": "resources/subscribe",
+                    "params": {"uri": "markets:active"}
+                })
+                .to_string(),
+            )
+            .send()
+            .await
+            .unwrap();
+        let session_id = response.headers()[SESSION_HEADER]
+            .to_str()
+            .unwrap()
+            .to_string();
+        assert!(!server.subscriptions.read().await.is_empty());
+
+        let events = http
+            .get(&url)
+            .header(SESSION_HEADER, &session_id)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(events.status(), StatusCode::OK);
+        drop(events);
+
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while !server.subscriptions.read().await.is_empty() {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+        })
+        .await
+        .unwrap();
+        let response = http
+            .post(&url)
+            .header(SESSION_HEADER, &session_id)
+            .body(json!({"jsonrpc": "2.0", "id": 2, "method": "tools/list"}).to_string())
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_reap_drops_only_idle_sessions_without_stream() {
+        let server = Arc::new(PolymarketMcpServer::with_config(test_config()).unwrap());
+        let sessions = HttpSessions::default();
+        for (id, streaming) in [("idle", false), ("streaming", true)] {
+            let (notifier, notifications) = mpsc::channel(NOTIFICATION_BUFFER);
+            sessions.lock().await.insert(
+                id.to_string(),
+                HttpSession {
+                    session: Session::new(notifier),
+                    notifications: (!streaming).then_some(notifications),
+                    last_active: Instant::now(),
+                },
+            );
+        }
+
+        assert_eq!(
+            reap_idle_sessions(&server, &sessions, Duration::from_secs(60)).await,
+            0
+        );
+        assert_eq!(
+            reap_idle_sessions(&server, &sessions, Duration::ZERO).await,
+            1
+        );
+        let sessions = sessions.lock().await;
+        assert!(sessions.contains_key("streaming"));
+        assert!(!sessions.contains_key("idle"));
+    }
+}
@@ -1,9 +1,16 @@
+pub mod arbitrage;
 pub mod config;
 pub mod error;
+pub mod export;
+pub mod market_url;
 pub mod models;
+pub mod notability;
 pub mod polymarket_client;
+pub mod prompt_templates;
+pub mod search;
 
 pub use config::Config;
-pub use error::{PolymarketError, RequestId, Result};
+pub use error::{PolymarketError, RequestId, Result, RetryStats};
 pub use models::*;
-pub use polymarket_client::PolymarketClient;
+pub use polymarket_client::{Metrics, PolymarketClient};
+pub use search::{KeywordMode, MatchMode, SearchFilters};
@@ -1,84 +1,806 @@
-mod config;
-mod error;
-mod models;
-mod polymarket_client;
-
-use anyhow::Result;
-use config::Config;
-use models::*;
-use polymarket_client::PolymarketClient;
+use polymarket_mcp::config::LOG_LEVELS;
+use polymarket_mcp::export;
+use polymarket_mcp::market_url::{self, MarketUrl};
+use polymarket_mcp::polymarket_client::{is_valid_address, is_valid_slug, PRICE_HISTORY_INTERVALS};
+use polymarket_mcp::prompt_templates::{self, PromptTemplate};
+use polymarket_mcp::{
+    models::*, Config, KeywordMode, MatchMode, Metrics, PolymarketClient, PolymarketError,
+    RequestId, Result, SearchFilters,
+};
+use serde::Serialize;
 use serde_json::{json, Value};
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, RwLock};
+use tokio::task::JoinHandle;
+use tracing::Instrument;
 use tracing_subscriber::{self, EnvFilter, FmtSubscriber};
 
+mod http_transport;
+
+/// Resources clients may subscribe to for `notifications/resources/updated`.
+const SUBSCRIBABLE_URIS: &[&str] = &["markets:active", "markets:trending"];
+
+/// How often subscribed resources are checked for a lapsed TTL
+const SUBSCRIPTION_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Most values returned by one `completion/complete` call, as the MCP spec allows
+const MAX_COMPLETIONS: usize = 100;
+
+/// Output fields holding a price or probability, rounded to `output.price_decimals`
+const PRICE_FIELDS: &[&str] = &[
+    "price",
+    "implied_probability",
+    "total_implied_probability",
+    "overround",
+    "best_bid",
+    "best_ask",
+    "mid",
+    "mid_price",
+    "spread",
+    "high_24h",
+    "low_24h",
+    "price_change_24h",
+];
+
+/// Band around the mid price used for `get_order_book` depth when none is given
+const DEFAULT_DEPTH_PCT: f64 = 2.0;
+
+/// Recent trades included in the `analyze_market` prompt
+const ANALYZE_RECENT_TRADES: u32 = 5;
+
+/// Widest window `get_markets_closing_soon` accepts, ten years in hours
+const MAX_CLOSING_WINDOW_HOURS: u32 = 10 * 366 * 24;
+
+/// Notifications queued per session before further ones are dropped
+const NOTIFICATION_BUFFER: usize = 64;
+
+/// Pause after a failed `accept`, so errors such as running out of file
+/// descriptors don't spin the accept loop
+const ACCEPT_ERROR_BACKOFF: Duration = Duration::from_millis(100);
+
+/// Notification senders keyed by resource URI, then by session id.
+type Subscribers = HashMap<String, HashMap<u64, mpsc::Sender<Value>>>;
+
 #[derive(Debug)]
 pub struct PolymarketMcpServer {
     client: Arc<PolymarketClient>,
     resource_cache: Arc<RwLock<HashMap<String, ResourceCache>>>,
-    config: Arc<Config>,
+    subscriptions: Arc<RwLock<Subscribers>>,
+    /// Last content seen by the subscription refresher per URI, minus `last_updated`
+    resource_versions: Arc<RwLock<HashMap<String, Value>>>,
+    log_filter: Option<LogFilter>,
+    /// Prompts loaded from `server.prompts_dir` at startup
+    prompt_templates: Arc<Vec<PromptTemplate>>,
+}
+
+/// Swaps the live `tracing` filter for one at the given level. Installed by `main`
+/// from the subscriber's reload handle; absent when logging isn't reloadable.
+struct LogFilter(Box<SetLogLevel>);
+
+type SetLogLevel = dyn Fn(&str) -> std::result::Result<(), String> + Send + Sync;
+
+impl std::fmt::Debug for LogFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("LogFilter")
+    }
+}
+
+/// A single client connection. Notifications for the connection are queued on
+/// `notifier` and written by the connection loop, so they never interleave with a
+/// response being written. The queue holds [`NOTIFICATION_BUFFER`] messages; a
+/// client that doesn't drain it misses notifications rather than growing it.
+#[derive(Clone)]
+struct Session {
+    id: u64,
+    notifier: mpsc::Sender<Value>,
+}
+
+impl Session {
+    fn new(notifier: mpsc::Sender<Value>) -> Self {
+        static NEXT_SESSION_ID: AtomicU64 = AtomicU64::new(1);
+        Self {
+            id: NEXT_SESSION_ID.fetch_add(1, Ordering::Relaxed),
+            notifier,
+        }
+    }
 }
 
 impl PolymarketMcpServer {
     pub fn new() -> Result<Self> {
-        let config = Arc::new(Config::load()?);
+        let config =
+            Arc::new(Config::load().map_err(|e| PolymarketError::config_error(format!("{e:#}")))?);
         let client = Arc::new(PolymarketClient::new_with_config(&config)?);
+        let prompt_templates = Arc::new(load_prompt_templates(&config)?);
         Ok(Self {
             client,
             resource_cache: Arc::new(RwLock::new(HashMap::new())),
-            config,
+            subscriptions: Arc::new(RwLock::new(HashMap::new())),
+            resource_versions: Arc::new(RwLock::new(HashMap::new())),
+            log_filter: None,
+            prompt_templates,
         })
     }
 
     pub fn with_config(config: Config) -> Result<Self> {
         let config = Arc::new(config);
         let client = Arc::new(PolymarketClient::new_with_config(&config)?);
+        let prompt_templates = Arc::new(load_prompt_templates(&config)?);
         Ok(Self {
             client,
             resource_cache: Arc::new(RwLock::new(HashMap::new())),
-            config,
+            subscriptions: Arc::new(RwLock::new(HashMap::new())),
+            resource_versions: Arc::new(RwLock::new(HashMap::new())),
+            log_filter: None,
+            prompt_templates,
+        })
+    }
+
+    /// Lets `set_log_level` and config reloads change the live `tracing` filter.
+    #[must_use]
+    pub fn with_log_filter<F>(mut self, set_level: F) -> Self
+    where
+        F: Fn(&str) -> std::result::Result<(), String> + Send + Sync + 'static,
+    {
+        self.log_filter = Some(LogFilter(Box::new(set_level)));
+        self
+    }
+
+    /// Applies the hot-reloadable fields of `config` (see
+    /// [`Config::with_reloadable_from`]) and returns the config sections whose
+    /// changes need a restart.
+    pub fn reload_config(&self, config: &Config) -> Vec<&'static str> {
+        let current = self.config();
+        let (merged, ignored) = current.with_reloadable_from(config);
+        if merged.logging.level != current.logging.level {
+            if let Err(e) = self.apply_log_level(&merged.logging.level) {
+                tracing::warn!("Failed to apply reloaded log level: {}", e);
+            }
+        }
+        self.client.reload_config(merged);
+        ignored
+    }
+
+    /// Changes the log level without a restart, returning the previous and new level.
+    ///
+    /// The new level is also recorded in the running config, so it survives until the
+    /// next reload or restart.
+    pub async fn set_log_level(&self, level: &str) -> Result<Value> {
+        let current = self.config();
+        self.apply_log_level(level)?;
+
+        let mut updated = (*current).clone();
+        updated.logging.level = level.to_string();
+        self.client.reload_config(updated);
+        tracing::info!(
+            "Log level changed from {} to {}",
+            current.logging.level,
+            level
+        );
+
+        Ok(json!({
+            "previous_level": current.logging.level,
+            "level": level
+        }))
+    }
+
+    fn apply_log_level(&self, level: &str) -> Result<()> {
+        let Some(LogFilter(set_level)) = &self.log_filter else {
+            return Err(PolymarketError::internal_error(
+                "Log level can't be changed: logging was not set up with a reloadable filter",
+            ));
+        };
+        set_level(level)
+            .map_err(|e| PolymarketError::internal_error(format!("Failed to set log level: {e}")))
+    }
+
+    /// Spawns a task that purges expired client and resource cache entries every
+    /// `interval`. The task runs until the returned handle is aborted.
+    pub fn spawn_cache_janitor(&self, interval: Duration) -> JoinHandle<()> {
+        let client = self.client.clone();
+        let resource_cache = self.resource_cache.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                let mut removed = client.purge_expired_cache().await;
+                {
+                    let mut cache = resource_cache.write().await;
+                    let before = cache.len();
+                    cache.retain(|_, entry| !entry.is_expired());
+                    removed += before - cache.len();
+                }
+                if removed > 0 {
+                    tracing::debug!("Cache janitor purged {} expired entries", removed);
+                }
+            }
+        })
+    }
+
+    /// Spawns a task that re-fetches subscribed resources as their TTL lapses and
+    /// notifies subscribers when the content changed. The task runs until the
+    /// returned handle is aborted.
+    pub fn spawn_subscription_refresher(self: &Arc<Self>) -> JoinHandle<()> {
+        let server = self.clone();
+        tokio::spawn(async move {
+            let mut refreshed_at = HashMap::new();
+            let mut ticker = tokio::time::interval(SUBSCRIPTION_POLL_INTERVAL);
+            loop {
+                ticker.tick().await;
+                server.refresh_subscriptions(&mut refreshed_at).await;
+            }
         })
     }
 
-    pub async fn get_active_markets(&self, limit: Option<u32>) -> Result<Value> {
-        let markets = self.client.get_active_markets(limit).await?;
+    /// Re-fetches every subscribed resource whose TTL has lapsed since
+    /// `refreshed_at`, refreshing the resource cache, and sends
+    /// `notifications/resources/updated` for those whose content changed. The first
+    /// fetch after a subscription only records a baseline. Returns the URIs notified.
+    async fn refresh_subscriptions(
+        &self,
+        refreshed_at: &mut HashMap<String, Instant>,
+    ) -> Vec<String> {
+        let uris: Vec<String> = self.subscriptions.read().await.keys().cloned().collect();
+        refreshed_at.retain(|uri, _| uris.contains(uri));
+        self.resource_versions
+            .write()
+            .await
+            .retain(|uri, _| uris.contains(uri));
+
+        let mut notified = Vec::new();
+        for uri in uris {
+            let ttl = self.config().resource_ttl_for(&uri);
+            if refreshed_at.get(&uri).is_some_and(|at| at.elapsed() < ttl) {
+                continue;
+            }
+            refreshed_at.insert(uri.clone(), Instant::now());
+
+            let content = match self.fetch_resource(&uri).await {
+                Ok(content) => content,
+                Err(e) => {
+                    tracing::warn!("Failed to refresh subscribed resource {}: {}", uri, e);
+                    continue;
+                }
+            };
+            self.cache_resource(&uri, &content).await;
+
+            let mut version: Value = serde_json::from_str(&content).unwrap_or_default();
+            if let Some(fields) = version.as_object_mut() {
+                fields.remove("last_updated");
+            }
+            let previous = self
+                .resource_versions
+                .write()
+                .await
+                .insert(uri.clone(), version.clone());
+            if previous.is_some_and(|previous| previous != version) {
+                self.notify_resource_updated(&uri).await;
+                notified.push(uri);
+            }
+        }
+        notified
+    }
+
+    /// Rounds the price and probability fields of a tool response to
+    /// `output.price_decimals`, hiding float noise such as `0.6000000000000001`.
+    fn round_prices(&self, mut value: Value) -> Value {
+        round_price_fields(&mut value, self.config().output.price_decimals);
+        value
+    }
+
+    /// Snapshot of the running configuration, shared with the client so reloads
+    /// apply to both.
+    fn config(&self) -> Arc<Config> {
+        self.client.config()
+    }
+
+    /// Resolves a tool's `limit` argument against its default and `api.max_limit`.
+    ///
+    /// A missing or zero limit falls back to `default`; anything larger than the
+    /// configured maximum is clamped to it.
+    fn effective_limit(&self, requested: Option<u32>, default: u32) -> u32 {
+        let limit = requested.filter(|&limit| limit > 0).unwrap_or(default);
+        limit.min(self.config().api.max_limit)
+    }
+
+    pub async fn get_active_markets(
+        &self,
+        limit: Option<u32>,
+        sort_by: MarketSort,
+        ascending: bool,
+        include_archived: Option<bool>,
+    ) -> Result<Value> {
+        let limit = self.effective_limit(limit, 50);
+        let include_archived = include_archived.unwrap_or(self.config().defaults.include_archived);
+        let markets = self
+            .client
+            .get_active_markets_sorted(Some(limit), sort_by, ascending, Some(include_archived))
+            .await?;
         Ok(json!({
             "markets": markets,
-            "count": markets.len()
+            "count": markets.len(),
+            "limit": limit,
+            "sort_by": sort_by,
+            "ascending": ascending,
+            "include_archived": include_archived
         }))
     }
 
-    pub async fn get_market_details(&self, market_id: String) -> Result<Value> {
+    pub async fn get_market_details(&self, market_id: String, include_book: bool) -> Result<Value> {
         let market = self.client.get_market_by_id(&market_id).await?;
+        let resolved_outcome = market.resolved_outcome();
+        let hours = market.time_to_resolution_hours(chrono::Utc::now());
+        let order_book = if include_book {
+            Some(self.client.get_top_of_book(&market).await)
+        } else {
+            None
+        };
+        let mut details = json!(market);
+        details["resolved_outcome"] = json!(resolved_outcome);
+        details["time_to_resolution_hours"] = json!(hours);
+        if let Some(order_book) = order_book {
+            details["order_book"] = json!(order_book);
+        }
+        Ok(self.round_prices(details))
+    }
+
+    pub async fn get_market_by_slug(&self, slug: String) -> Result<Value> {
+        let market = self.client.get_market_by_slug(&slug).await?;
         Ok(json!(market))
     }
 
-    pub async fn search_markets(&self, keyword: String, limit: Option<u32>) -> Result<Value> {
-        let markets = self.client.search_markets(&keyword, limit).await?;
+    pub async fn resolve_market_url(&self, url: String, target: MarketUrl) -> Result<Value> {
+        let markets = match &target {
+            MarketUrl::Market { slug }
+            | MarketUrl::Event {
+                market_slug: Some(slug),
+                ..
+            } => vec![self.client.get_market_by_slug(slug).await?],
+            MarketUrl::Event {
+                event_slug,
+                market_slug: None,
+            } => self
+                .client
+                .get_event_by_slug(event_slug)
+                .await?
+                .markets
+                .unwrap_or_default(),
+        };
+        Ok(json!({
+            "url": url,
+            "resolved": target,
+            "markets": markets,
+            "count": markets.len()
+        }))
+    }
+
+    pub async fn search_markets(
+        &self,
+        keywords: Vec<String>,
+        keyword_mode: KeywordMode,
+        limit: Option<u32>,
+        match_mode: MatchMode,
+        filters: SearchFilters,
+    ) -> Result<Value> {
+        let limit = self.effective_limit(limit, 20);
+        let markets = self
+            .client
+            .search_markets(&keywords, keyword_mode, Some(limit), match_mode, &filters)
+            .await?;
         Ok(json!({
             "markets": markets,
             "count": markets.len(),
-            "keyword": keyword
+            "limit": limit,
+            "keywords": keywords,
+            "mode": keyword_mode,
+            "match_mode": match_mode,
+            "filters": filters
         }))
     }
 
+    /// Renders active markets, or those matching `keywords`, as CSV.
+    pub async fn export_markets_csv(
+        &self,
+        keywords: Option<Vec<String>>,
+        limit: Option<u32>,
+    ) -> Result<String> {
+        let limit = self.effective_limit(limit, 50);
+        let markets = match keywords {
+            Some(keywords) => {
+                self.client
+                    .search_markets(
+                        &keywords,
+                        KeywordMode::default(),
+                        Some(limit),
+                        MatchMode::default(),
+                        &SearchFilters::default(),
+                    )
+                    .await?
+            }
+            None => self.client.get_active_markets(Some(limit)).await?,
+        };
+        Ok(export::markets_to_csv(&markets))
+    }
+
     pub async fn get_market_prices(&self, market_id: String) -> Result<Value> {
         let prices = self.client.get_market_prices(&market_id).await?;
+        Ok(self.round_prices(price_response(&market_id, &prices)))
+    }
+
+    pub async fn get_market_outcomes(&self, market_id: String) -> Result<Value> {
+        let market = self.client.get_market_by_id(&market_id).await?;
+        let outcomes = market.structured_outcomes();
+        Ok(self.round_prices(json!({
+            "market_id": market_id,
+            "outcomes": outcomes,
+            "count": outcomes.len()
+        })))
+    }
+
+    pub async fn get_trending_markets(&self, limit: Option<u32>) -> Result<Value> {
+        let limit = self.effective_limit(limit, 10);
+        let markets = self.client.get_trending_markets(Some(limit)).await?;
+        Ok(json!({
+            "markets": markets,
+            "count": markets.len(),
+            "limit": limit
+        }))
+    }
+
+    pub async fn get_order_book(
+        &self,
+        market_id: String,
+        outcome_id: Option<String>,
+        depth_pct: f64,
+    ) -> Result<Value> {
+        let outcome_id = match outcome_id {
+            Some(outcome_id) => outcome_id,
+            None => {
+                let market = self.client.get_market_by_id(&market_id).await?;
+                market.outcomes.first().cloned().ok_or_else(|| {
+                    PolymarketError::internal_error(format!("Market {market_id} has no outcomes"))
+                })?
+            }
+        };
+        let order_book = self.client.get_order_book(&market_id, &outcome_id).await?;
+        let mut response = json!(order_book);
+        response["spread"] = json!(order_book.spread());
+        response["mid_price"] = json!(order_book.mid());
+        response["depth_within_pct"] = json!(order_book.depth_within(depth_pct));
+        Ok(self.round_prices(response))
+    }
+
+    pub async fn get_market_trades(&self, market_id: String, limit: Option<u32>) -> Result<Value> {
+        let limit = self.effective_limit(limit, 50);
+        let trades = self.client.get_trades(&market_id, Some(limit)).await?;
         Ok(json!({
             "market_id": market_id,
-            "prices": prices
+            "trades": trades,
+            "count": trades.len(),
+            "limit": limit
         }))
     }
 
-    pub async fn get_trending_markets(&self, limit: Option<u32>) -> Result<Value> {
-        let markets = self.client.get_trending_markets(limit).await?;
+    pub async fn find_arbitrage(&self, keyword: String, limit: Option<u32>) -> Result<Value> {
+        let limit = self.effective_limit(limit, 20);
+        let (_, opportunities) = self.client.find_arbitrage(&keyword, Some(limit)).await?;
+        Ok(json!({
+            "keyword": keyword,
+            "opportunities": opportunities,
+            "count": opportunities.len(),
+            "limit": limit
+        }))
+    }
+
+    pub async fn find_similar_markets(
+        &self,
+        market_id: String,
+        limit: Option<u32>,
+    ) -> Result<Value> {
+        let limit = self.effective_limit(limit, 10);
+        let (source, similar) = self
+            .client
+            .find_similar_markets(&market_id, Some(limit))
+            .await?;
+        Ok(json!({
+            "source": source,
+            "similar": similar,
+            "count": similar.len(),
+            "limit": limit
+        }))
+    }
+
+    pub async fn get_positions(&self, user_address: String) -> Result<Value> {
+        let positions = self.client.get_positions(&user_address).await?;
+        Ok(json!({
+            "user_address": user_address,
+            "positions": positions,
+            "count": positions.len()
+        }))
+    }
+
+    pub async fn get_events(&self, limit: Option<u32>, active: Option<bool>) -> Result<Value> {
+        let limit = self.effective_limit(limit, 20);
+        let events = self.client.get_events(Some(limit), active).await?;
+        Ok(json!({
+            "events": events,
+            "count": events.len(),
+            "limit": limit
+        }))
+    }
+
+    pub async fn get_event_markets(&self, event_id: String) -> Result<Value> {
+        let event = self.client.get_event(&event_id).await?;
+        let markets = event.markets.unwrap_or_default();
         Ok(json!({
+            "event_id": event_id,
+            "title": event.title,
             "markets": markets,
             "count": markets.len()
         }))
     }
 
+    pub async fn get_price_history(&self, market_id: String, interval: String) -> Result<Value> {
+        let points = self.client.get_price_history(&market_id, &interval).await?;
+
+        let mut outcomes: Vec<(String, Vec<Value>)> = Vec::new();
+        for point in points {
+            let entry = json!({ "timestamp": point.timestamp, "price": point.price });
+            match outcomes
+                .iter_mut()
+                .find(|(outcome, _)| *outcome == point.outcome)
+            {
+                Some((_, series)) => series.push(entry),
+                None => outcomes.push((point.outcome, vec![entry])),
+            }
+        }
+        let outcomes: Vec<Value> = outcomes
+            .into_iter()
+            .map(|(outcome, points)| json!({ "outcome": outcome, "points": points }))
+            .collect();
+
+        Ok(json!({
+            "market_id": market_id,
+            "interval": interval,
+            "outcomes": outcomes
+        }))
+    }
+
+    pub async fn get_market_sentiment(&self, market_id: String) -> Result<Value> {
+        let sentiment = self.client.market_sentiment(&market_id).await?;
+        Ok(json!(sentiment))
+    }
+
+    pub async fn get_market_stats(&self, market_id: String) -> Result<Value> {
+        let stats = self.client.get_market_stats(&market_id).await?;
+        Ok(self.round_prices(json!(stats)))
+    }
+
+    pub async fn list_categories(&self) -> Result<Value> {
+        let categories = self.client.list_categories().await?;
+        let categories: Vec<Value> = categories
+            .into_iter()
+            .map(|(category, count)| json!({ "category": category, "count": count }))
+            .collect();
+        Ok(json!({
+            "count": categories.len(),
+            "categories": categories
+        }))
+    }
+
+    pub async fn get_notable_markets(&self, limit: Option<u32>) -> Result<Value> {
+        let limit = self.effective_limit(limit, 10);
+        let notable = self.client.score_notable_markets(limit, None).await?;
+        Ok(self.round_prices(json!({
+            "markets": notable,
+            "count": notable.len(),
+            "limit": limit
+        })))
+    }
+
+    pub async fn market_overview(&self, sample_size: Option<u32>) -> Result<Value> {
+        let default = self.config().analytics.overview_sample_size;
+        let sample_size = self.effective_limit(sample_size, default);
+        let overview = self.client.market_overview(sample_size).await?;
+        Ok(json!(overview))
+    }
+
+    pub async fn get_markets_by_category(
+        &self,
+        category: String,
+        limit: Option<u32>,
+    ) -> Result<Value> {
+        let limit = self.effective_limit(limit, 20);
+        let (markets, tag_id) = self
+            .client
+            .get_markets_by_category(&category, Some(limit))
+            .await?;
+        Ok(json!({
+            "category": category,
+            "tag_id": tag_id,
+            "filter": if tag_id.is_some() { "tag" } else { "category_field" },
+            "markets": markets,
+            "count": markets.len(),
+            "limit": limit
+        }))
+    }
+
+    pub async fn get_tag_markets(
+        &self,
+        tag_id: String,
+        related_tags: Option<bool>,
+        limit: Option<u32>,
+    ) -> Result<Value> {
+        let limit = self.effective_limit(limit, 20);
+        let (markets, query) = self
+            .client
+            .get_tag_markets(&tag_id, related_tags, Some(limit))
+            .await?;
+        Ok(json!({
+            "tag_id": tag_id,
+            "related_tags": related_tags,
+            "query": query,
+            "markets": markets,
+            "count": markets.len(),
+            "limit": limit
+        }))
+    }
+
+    pub async fn get_markets_closing_soon(
+        &self,
+        within_hours: u32,
+        limit: Option<u32>,
+    ) -> Result<Value> {
+        let limit = self.effective_limit(limit, 20);
+        let markets = self
+            .client
+            .get_markets_closing_soon(within_hours, Some(limit))
+            .await?;
+        Ok(json!({
+            "markets": markets,
+            "count": markets.len(),
+            "within_hours": within_hours,
+            "limit": limit
+        }))
+    }
+
+    pub async fn get_new_markets(
+        &self,
+        since: chrono::DateTime<chrono::Utc>,
+        limit: Option<u32>,
+    ) -> Result<Value> {
+        let limit = self.effective_limit(limit, 20);
+        let markets = self.client.get_new_markets(since, Some(limit)).await?;
+        Ok(json!({
+            "markets": markets,
+            "count": markets.len(),
+            "since": since.to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            "limit": limit
+        }))
+    }
+
+    pub async fn get_resolved_markets(&self, limit: Option<u32>) -> Result<Value> {
+        let limit = self.effective_limit(limit, 20);
+        let markets = self.client.get_resolved_markets(Some(limit)).await?;
+        let resolved: Vec<Value> = markets
+            .iter()
+            .map(|market| {
+                let final_prices: Vec<Option<f64>> = market
+                    .outcome_prices
+                    .iter()
+                    .map(|price| price.parse().ok())
+                    .collect();
+                json!({
+                    "id": market.id,
+                    "question": market.question,
+                    "slug": market.slug,
+                    "end_date": market.end_date,
+                    "outcomes": market.outcomes,
+                    "final_prices": final_prices,
+                    "resolved_outcome": market.resolved_outcome()
+                })
+            })
+            .collect();
+        Ok(json!({
+            "markets": resolved,
+            "count": resolved.len(),
+            "limit": limit
+        }))
+    }
+
+    pub async fn get_markets_batch(&self, market_ids: Vec<String>) -> Result<Value> {
+        let result = self.client.get_markets_batch(&market_ids).await?;
+        let failed: Vec<Value> = result
+            .failed
+            .iter()
+            .map(|(market_id, error)| json!({ "market_id": market_id, "error": error }))
+            .collect();
+        Ok(json!({
+            "found": result.found,
+            "failed": failed,
+            "found_count": result.found.len(),
+            "failed_count": failed.len()
+        }))
+    }
+
+    /// Drops cached data so the next request refetches it.
+    ///
+    /// With a `uri` only that resource (and, for `market:<id>`, the market itself) is
+    /// dropped; with a `market_id` the market and its resource are dropped; with neither,
+    /// every cache is cleared.
+    pub async fn invalidate_cache(
+        &self,
+        uri: Option<String>,
+        market_id: Option<String>,
+    ) -> Result<Value> {
+        let mut resource_cache = self.resource_cache.write().await;
+        let evicted = match (uri, market_id) {
+            (None, None) => {
+                let resources = resource_cache.len();
+                resource_cache.clear();
+                resources + self.client.clear_cache().await
+            }
+            (uri, market_id) => {
+                let market_id = market_id.or_else(|| {
+                    uri.as_deref()
+                        .and_then(|uri| uri.strip_prefix("market:"))
+                        .map(str::to_string)
+                });
+                let mut evicted = 0;
+                if let Some(uri) = &uri {
+                    evicted += usize::from(resource_cache.remove(uri).is_some());
+                }
+                if let Some(market_id) = &market_id {
+                    let market_uri = format!("market:{market_id}");
+                    if uri.as_deref() != Some(market_uri.as_str()) {
+                        evicted += usize::from(resource_cache.remove(&market_uri).is_some());
+                    }
+                    evicted += self.client.invalidate_market(market_id).await;
+                }
+                evicted
+            }
+        };
+
+        Ok(json!({ "evicted": evicted }))
+    }
+
+    /// The client's metrics, plus the size of the server's own resource cache.
+    pub async fn metrics_snapshot(&self) -> Metrics {
+        let mut metrics = self.client.get_metrics().await;
+        metrics.resource_cache_size = self.resource_cache.read().await.len();
+        metrics
+    }
+
+    pub async fn get_metrics(&self) -> Result<Value> {
+        let metrics = self.metrics_snapshot().await;
+        Ok(json!({
+            "api_requests_total": metrics.api_requests_total,
+            "api_failures": metrics.api_failures,
+            "cache_hits": metrics.cache_hits,
+            "cache_misses": metrics.cache_misses,
+            "markets_skipped": metrics.markets_skipped,
+            "avg_response_time_ms": metrics.avg_response_time,
+            "cache_hit_ratio": metrics.cache_hit_ratio(),
+            "failure_rate": metrics.failure_rate(),
+            "coalesced_requests": metrics.coalesced_requests,
+            "tool_calls": metrics.tool_calls,
+            "circuit_state": metrics.circuit_state,
+            "circuit_states": metrics.circuit_states,
+            "cache_sizes": {
+                "market": metrics.market_cache_size,
+                "single_market": metrics.single_market_cache_size,
+                "resource": metrics.resource_cache_size
+            }
+        }))
+    }
+
+    pub async fn health(&self) -> Result<Value> {
+        Ok(json!(self.client.health_check().await))
+    }
+
     // MCP Resources Support
     pub async fn list_resources(&self) -> Result<Value> {
         let resources = vec![
@@ -94,6 +816,12 @@ impl PolymarketMcpServer {
                 description: "Markets with highest trading volume".to_string(),
                 mime_type: "application/json".to_string(),
             },
+            McpResource {
+                uri: "markets:watchlist".to_string(),
+                name: "Watchlist".to_string(),
+                description: "Markets pinned by the server's watchlist configuration".to_string(),
+                mime_type: "application/json".to_string(),
+            },
         ];
         Ok(json!({ "resources": resources }))
     }
@@ -114,6 +842,20 @@ impl PolymarketMcpServer {
             }
         }
 
+        let content = self.fetch_resource(uri).await?;
+        self.cache_resource(uri, &content).await;
+
+        Ok(json!({
+            "contents": [{
+                "uri": uri,
+                "mimeType": "application/json",
+                "text": content
+            }]
+        }))
+    }
+
+    /// Fetches the current content of `uri`, bypassing the resource cache.
+    async fn fetch_resource(&self, uri: &str) -> Result<String> {
         let content = match uri {
             "markets:active" => {
                 let markets = self.client.get_active_markets(Some(20)).await?;
@@ -131,29 +873,103 @@ impl PolymarketMcpServer {
                     "last_updated": chrono::Utc::now().to_rfc3339()
                 }))?
             }
+            "markets:watchlist" => {
+                let watchlist = self.config().watchlist.clone();
+                let result = if watchlist.is_empty() {
+                    BatchResult::default()
+                } else {
+                    self.client.get_markets_batch(&watchlist).await?
+                };
+                let failed: Vec<Value> = result
+                    .failed
+                    .iter()
+                    .map(|(market_id, error)| json!({ "market_id": market_id, "error": error }))
+                    .collect();
+                serde_json::to_string_pretty(&json!({
+                    "markets": result.found,
+                    "count": result.found.len(),
+                    "failed": failed,
+                    "last_updated": chrono::Utc::now().to_rfc3339()
+                }))?
+            }
             _ if uri.starts_with("market:") => {
                 let market_id = uri.strip_prefix("market:").unwrap();
                 let market = self.client.get_market_by_id(market_id).await?;
                 serde_json::to_string_pretty(&market)?
             }
             _ => {
-                return Err(anyhow::anyhow!("Unknown resource URI: {}", uri));
+                return Err(PolymarketError::resource_error(format!(
+                    "Unknown resource URI: {uri}"
+                )));
             }
         };
 
-        if self.config.cache.enabled {
+        Ok(content)
+    }
+
+    async fn cache_resource(&self, uri: &str, content: &str) {
+        let config = self.config();
+        if config.cache.enabled {
             let mut cache = self.resource_cache.write().await;
-            let ttl = self.config.resource_cache_ttl().as_secs();
-            cache.insert(uri.to_string(), ResourceCache::new(content.clone(), ttl));
+            let ttl = config.resource_ttl_for(uri).as_secs();
+            cache.insert(
+                uri.to_string(),
+                ResourceCache::new(content.to_string(), ttl),
+            );
         }
+    }
 
-        Ok(json!({
-            "contents": [{
-                "uri": uri,
-                "mimeType": "application/json",
-                "text": content
-            }]
-        }))
+    /// Registers `session` for update notifications on `uri`.
+    async fn subscribe(&self, session: &Session, uri: &str) {
+        self.subscriptions
+            .write()
+            .await
+            .entry(uri.to_string())
+            .or_default()
+            .insert(session.id, session.notifier.clone());
+    }
+
+    async fn unsubscribe(&self, session: &Session, uri: &str) {
+        let mut subscriptions = self.subscriptions.write().await;
+        if let Some(sessions) = subscriptions.get_mut(uri) {
+            sessions.remove(&session.id);
+            if sessions.is_empty() {
+                subscriptions.remove(uri);
+            }
+        }
+    }
+
+    /// Drops every subscription held by a closed connection.
+    async fn end_session(&self, session: &Session) {
+        let mut subscriptions = self.subscriptions.write().await;
+        subscriptions.retain(|_, sessions| {
+            sessions.remove(&session.id);
+            !sessions.is_empty()
+        });
+    }
+
+    /// Queues `notifications/resources/updated` for every subscriber of `uri`.
+    async fn notify_resource_updated(&self, uri: &str) {
+        let subscriptions = self.subscriptions.read().await;
+        let Some(sessions) = subscriptions.get(uri) else {
+            return;
+        };
+        let notification = json!({
+            "jsonrpc": "2.0",
+            "method": "notifications/resources/updated",
+            "params": { "uri": uri }
+        });
+        for (session_id, notifier) in sessions {
+            // A closed receiver means the connection is going away; end_session cleans up
+            if let Err(mpsc::error::TrySendError::Full(_)) = notifier.try_send(notification.clone())
+            {
+                tracing::warn!(
+                    "Notification queue full for session {}; dropping update for {}",
+                    session_id,
+                    uri
+                );
+            }
+        }
     }
 
     // MCP Prompts Support
@@ -186,6 +1002,17 @@ impl PolymarketMcpServer {
                     }
                 ],
             },
+            McpPrompt {
+                name: "portfolio_summary".to_string(),
+                description: "Summarize a wallet's exposure, biggest winners and losers, and concentration risk".to_string(),
+                arguments: vec![
+                    McpPromptArgument {
+                        name: "user_address".to_string(),
+                        description: "Wallet address (0x followed by 40 hex characters)".to_string(),
+                        required: true,
+                    }
+                ],
+            },
             McpPrompt {
                 name: "market_summary".to_string(),
                 description: "Provide a comprehensive summary of the top prediction markets".to_string(),
@@ -204,33 +1031,92 @@ impl PolymarketMcpServer {
             },
         ];
 
+        let mut prompts: Vec<McpPrompt> = prompts
+            .into_iter()
+            .filter(|prompt| self.prompt_template(&prompt.name).is_none())
+            .collect();
+        prompts.extend(self.prompt_templates.iter().map(PromptTemplate::to_prompt));
+
         Ok(json!({ "prompts": prompts }))
     }
 
+    fn prompt_template(&self, name: &str) -> Option<&PromptTemplate> {
+        self.prompt_templates
+            .iter()
+            .find(|template| template.name == name)
+    }
+
+    /// Suggests values for a prompt argument from the partial `value` typed so far.
+    ///
+    /// `analyze_market`'s `market_id` completes from active market ids and
+    /// `market_summary`'s `category` from the known categories, both by
+    /// case-insensitive prefix. Other arguments get no suggestions.
+    pub async fn complete(&self, prompt: &str, argument: &str, value: &str) -> Result<Value> {
+        let candidates: Vec<String> = match (prompt, argument) {
+            ("analyze_market", "market_id") => self
+                .client
+                .get_active_markets(Some(MAX_COMPLETIONS as u32))
+                .await?
+                .into_iter()
+                .map(|market| market.id)
+                .collect(),
+            ("market_summary", "category") => self
+                .client
+                .list_categories()
+                .await?
+                .into_iter()
+                .map(|(category, _)| category)
+                .collect(),
+            _ => Vec::new(),
+        };
+        Ok(completion_result(candidates, value))
+    }
+
     pub async fn get_prompt(&self, name: &str, arguments: Option<Value>) -> Result<Value> {
         let args = arguments.unwrap_or_default();
 
+        // Prompts from `server.prompts_dir` take precedence over the built-in ones
+        if let Some(template) = self.prompt_template(name) {
+            let messages = vec![McpPromptMessage {
+                role: "user".to_string(),
+                content: McpPromptContent::Text(template.render(&args)?),
+            }];
+            return Ok(json!({ "messages": messages }));
+        }
+
         let messages = match name {
             "analyze_market" => {
-                let market_id = args
-                    .get("market_id")
-                    .and_then(|v| v.as_str())
-                    .ok_or_else(|| anyhow::anyhow!("market_id argument is required"))?;
+                let market_id =
+                    args.get("market_id")
+                        .and_then(|v| v.as_str())
+                        .ok_or_else(|| {
+                            PolymarketError::invalid_argument("market_id", "argument is required")
+                        })?;
 
                 let market = self.client.get_market_by_id(market_id).await?;
                 let prices = self.client.get_market_prices(market_id).await?;
+                // Book and trades are extras; the prompt renders without them if they fail
+                let (books, trades) = tokio::join!(
+                    self.client.get_top_of_book(&market),
+                    self.client
+                        .get_trades(market_id, Some(ANALYZE_RECENT_TRADES))
+                );
+                let trades = trades.map_err(|e| {
+                    tracing::warn!("Failed to load trades for market {}: {}", market_id, e);
+                });
 
                 vec![
                     McpPromptMessage {
                         role: "user".to_string(),
                         content: McpPromptContent::Text(format!(
-                            "Analyze this prediction market:\n\nMarket: {}\nQuestion: {}\nLiquidity: ${:.0}\nVolume: ${:.0}\nActive: {}\n\nCurrent Prices:\n{}\n\nProvide analysis on:\n1. Market sentiment and trends\n2. Liquidity assessment\n3. Price efficiency\n4. Potential trading opportunities\n5. Risk factors",
+                            "Analyze this prediction market:\n\nMarket: {}\nQuestion: {}\nLiquidity: ${:.0}\nVolume: ${:.0}\nActive: {}\n\nCurrent Prices:\n{}\n\n{}\n\nProvide analysis on:\n1. Market sentiment and trends\n2. Liquidity assessment\n3. Price efficiency\n4. Potential trading opportunities\n5. Risk factors",
                             market.id,
                             market.question,
                             market.liquidity,
                             market.volume,
                             market.active,
-                            serde_json::to_string_pretty(&prices)?
+                            serde_json::to_string_pretty(&prices)?,
+                            market_activity_text(&books, trades.as_deref().ok())
                         ))
                     }
                 ]
@@ -239,24 +1125,24 @@ impl PolymarketMcpServer {
                 let keyword = args
                     .get("keyword")
                     .and_then(|v| v.as_str())
-                    .ok_or_else(|| anyhow::anyhow!("keyword argument is required"))?;
-
-                let limit = args
-                    .get("limit")
-                    .and_then(|v| v.as_u64())
-                    .map(|l| l as u32)
-                    .unwrap_or(10);
+                    .ok_or_else(|| {
+                        PolymarketError::invalid_argument("keyword", "argument is required")
+                    })?;
 
-                let markets = self.client.search_markets(keyword, Some(limit)).await?;
+                let limit = self.effective_limit(optional_u32(&args, "limit"), 10);
+                // One search feeds both the market list and the opportunities
+                let (markets, opportunities) =
+                    self.client.find_arbitrage(keyword, Some(limit)).await?;
 
                 vec![
                     McpPromptMessage {
                         role: "user".to_string(),
                         content: McpPromptContent::Text(format!(
-                            "Find arbitrage opportunities among these related markets:\n\nKeyword: {}\nMarkets found: {}\n\n{}\n\nAnalyze:\n1. Similar questions with different prices\n2. Cross-market arbitrage opportunities\n3. Risk-adjusted returns\n4. Execution feasibility\n5. Recommended actions",
+                            "Find arbitrage opportunities among these related markets:\n\nKeyword: {}\nMarkets found: {}\n\n{}\n\nDetected opportunities:\n{}\n\nAnalyze:\n1. Similar questions with different prices\n2. Cross-market arbitrage opportunities\n3. Risk-adjusted returns\n4. Execution feasibility\n5. Recommended actions",
                             keyword,
                             markets.len(),
-                            serde_json::to_string_pretty(&markets)?
+                            serde_json::to_string_pretty(&markets)?,
+                            serde_json::to_string_pretty(&opportunities)?
                         ))
                     }
                 ]
@@ -268,22 +1154,79 @@ impl PolymarketMcpServer {
                     .map(|l| l as u32)
                     .unwrap_or(5);
 
-                let trending = self.client.get_trending_markets(Some(limit)).await?;
-                let active = self.client.get_active_markets(Some(limit)).await?;
+                let category = args
+                    .get("category")
+                    .and_then(|v| v.as_str())
+                    .map(str::trim)
+                    .filter(|c| !c.is_empty());
 
-                vec![
-                    McpPromptMessage {
-                        role: "user".to_string(),
-                        content: McpPromptContent::Text(format!(
-                            "Provide a comprehensive market summary:\n\nTop Trending Markets (by volume):\n{}\n\nTop Active Markets:\n{}\n\nSummarize:\n1. Overall market sentiment\n2. Popular categories and themes\n3. Liquidity distribution\n4. Notable price movements\n5. Trading recommendations",
-                            serde_json::to_string_pretty(&trending)?,
-                            serde_json::to_string_pretty(&active)?
-                        ))
+                let notable = self.client.notable_markets(limit, category).await?;
+                let active = match category {
+                    Some(category) => {
+                        self.client
+                            .get_markets_by_category(category, Some(limit))
+                            .await?
+                            .0
                     }
-                ]
+                    None => self.client.get_active_markets(Some(limit)).await?,
+                };
+
+                let text = match category {
+                    Some(category) if notable.is_empty() && active.is_empty() => format!(
+                        "There are currently no active markets in the '{category}' category. Say so, and suggest related categories the user could look at instead."
+                    ),
+                    _ => format!(
+                        "Provide a comprehensive market summary{}:\n\nNotable Markets (ranked by 24h volume surge, liquidity and time to close):\n{}\n\nTop Active Markets:\n{}\n\nSummarize:\n1. Overall market sentiment\n2. Popular categories and themes\n3. Liquidity distribution\n4. Notable price movements\n5. Trading recommendations",
+                        category
+                            .map(|category| format!(" for the '{category}' category"))
+                            .unwrap_or_default(),
+                        serde_json::to_string_pretty(&notable)?,
+                        serde_json::to_string_pretty(&active)?
+                    ),
+                };
+
+                vec![McpPromptMessage {
+                    role: "user".to_string(),
+                    content: McpPromptContent::Text(text),
+                }]
+            }
+            "portfolio_summary" => {
+                let user_address = args
+                    .get("user_address")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| {
+                        PolymarketError::invalid_argument("user_address", "argument is required")
+                    })?;
+
+                let positions = self.client.get_positions(user_address).await?;
+                let text = if positions.is_empty() {
+                    format!("No positions were found for wallet {user_address}.")
+                } else {
+                    let mut condition_ids: Vec<String> =
+                        positions.iter().map(|p| p.market_id.clone()).collect();
+                    condition_ids.sort();
+                    condition_ids.dedup();
+                    // Market context is a nice-to-have; summarize positions alone if it fails
+                    let markets = self
+                        .client
+                        .get_markets_by_condition_ids(&condition_ids)
+                        .await
+                        .unwrap_or_else(|e| {
+                            tracing::warn!("Failed to load markets for positions: {}", e);
+                            Vec::new()
+                        });
+                    portfolio_summary_text(user_address, &positions, &markets)
+                };
+
+                vec![McpPromptMessage {
+                    role: "user".to_string(),
+                    content: McpPromptContent::Text(text),
+                }]
             }
             _ => {
-                return Err(anyhow::anyhow!("Unknown prompt: {}", name));
+                return Err(PolymarketError::resource_error(format!(
+                    "Unknown prompt: {name}"
+                )));
             }
         };
 
@@ -291,12 +1234,15 @@ impl PolymarketMcpServer {
     }
 }
 
-use clap::{Arg, Command};
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader as AsyncBufReader};
+use clap::{Arg, ArgAction, Command};
+use tokio::io::{
+    AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt, BufReader as AsyncBufReader,
+};
+use tokio::net::TcpListener;
 use tokio::signal;
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> anyhow::Result<()> {
     // Parse command line arguments
     let matches = Command::new("polymarket-mcp")
         .version(env!("CARGO_PKG_VERSION"))
@@ -324,20 +1270,35 @@ async fn main() -> Result<()> {
                 .help("Port to listen on (for TCP mode)")
                 .value_parser(clap::value_parser!(u16)),
         )
+        .arg(
+            Arg::new("host")
+                .long("host")
+                .value_name("HOST")
+                .help("Address to bind in TCP or HTTP mode")
+                .default_value("127.0.0.1"),
+        )
+        .arg(
+            Arg::new("http")
+                .long("http")
+                .help("Serve MCP over HTTP with Server-Sent Events (port defaults to 8080)")
+                .action(ArgAction::SetTrue),
+        )
         .get_matches();
 
     // Load environment variables from .env file if it exists
     dotenv::dotenv().ok();
 
     // Load configuration with optional config file override
-    let mut config = Config::load()?;
-    if let Some(config_path) = matches.get_one::<String>("config") {
-        config = Config::load_from_file(config_path)?;
-    }
+    let config_path = matches.get_one::<String>("config").cloned();
+    let mut config = match &config_path {
+        Some(config_path) => Config::load_with_file(config_path)?,
+        None => Config::load()?,
+    };
 
     // Override log level if specified
-    if let Some(log_level) = matches.get_one::<String>("log-level") {
-        config.logging.level = log_level.clone();
+    let log_level_override = matches.get_one::<String>("log-level").cloned();
+    if let Some(log_level) = &log_level_override {
+        config.logging.level.clone_from(log_level);
     }
 
     // Initialize tracing subscriber to write to stderr only
@@ -345,14 +1306,38 @@ async fn main() -> Result<()> {
         EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(&config.logging.level));
 
     // Write logs to stderr to avoid interfering with MCP JSON protocol on stdout
-    FmtSubscriber::builder()
+    let subscriber = FmtSubscriber::builder()
         .with_env_filter(env_filter)
         .with_writer(std::io::stderr)
         .compact()
-        .init();
+        .with_filter_reloading();
+    let log_filter = subscriber.reload_handle();
+    subscriber.init();
 
     // Create the MCP server handler with configuration
-    let server = Arc::new(PolymarketMcpServer::with_config(config)?);
+    let server = Arc::new(PolymarketMcpServer::with_config(config)?.with_log_filter(
+        move |level| {
+            log_filter
+                .reload(EnvFilter::new(level))
+                .map_err(|e| e.to_string())
+        },
+    ));
+
+    let janitor = server
+        .config()
+        .cache
+        .enabled
+        .then(|| server.spawn_cache_janitor(server.config().cache_cleanup_interval()));
+    let refresher = server.spawn_subscription_refresher();
+
+    #[cfg(unix)]
+    tokio::spawn(reload_on_sighup(
+        server.clone(),
+        config_path,
+        log_level_override,
+    ));
+    #[cfg(not(unix))]
+    drop((config_path, log_level_override));
 
     // Set up graceful shutdown handling
     let shutdown_signal = async {
@@ -361,56 +1346,427 @@ async fn main() -> Result<()> {
             .expect("Failed to install CTRL+C signal handler");
     };
 
-    // Set up MCP server using stdin/stdout
-    let stdin = tokio::io::stdin();
-    let stdout = tokio::io::stdout();
+    // Main server loop with graceful shutdown
+    let host = matches
+        .get_one::<String>("host")
+        .map(String::as_str)
+        .unwrap_or("127.0.0.1");
+    let port = matches.get_one::<u16>("port").copied();
+    // Serve inside a block so the cleanup below runs however serving ends
+    let served: anyhow::Result<()> = async {
+        if matches.get_flag("http") {
+            let listener = TcpListener::bind((host, port.unwrap_or(8080))).await?;
+            tracing::info!(
+                "Listening for MCP over HTTP on http://{}/mcp",
+                listener.local_addr()?
+            );
+
+            tokio::select! {
+                _ = shutdown_signal => {}
+                result = http_transport::serve_http(server.clone(), listener) => result?,
+            }
+        } else {
+            match port {
+                Some(port) => {
+                    let listener = TcpListener::bind((host, port)).await?;
+                    tracing::info!(
+                        "Listening for MCP connections on {}",
+                        listener.local_addr()?
+                    );
+
+                    tokio::select! {
+                        _ = shutdown_signal => {}
+                        result = serve_tcp(server.clone(), listener) => result?,
+                    }
+                }
+                None => {
+                    // Set up MCP server using stdin/stdout
+                    let reader = AsyncBufReader::new(tokio::io::stdin());
+                    let writer = tokio::io::stdout();
+
+                    tokio::select! {
+                        _ = shutdown_signal => {}
+                        _ = serve_connection(server.clone(), reader, writer) => {}
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+    .await;
 
-    let mut reader = AsyncBufReader::new(stdin);
-    let mut writer = stdout;
+    if let Some(janitor) = janitor {
+        janitor.abort();
+    }
+    refresher.abort();
+    if let Err(e) = server.client.save_cache().await {
+        tracing::warn!("Failed to persist cache: {}", e);
+    }
 
-    let mut line = String::new();
+    served
+}
 
-    // Main server loop with graceful shutdown
-    tokio::select! {
-        _ = shutdown_signal => {}
-        _ = async {
-            loop {
-                line.clear();
-                match reader.read_line(&mut line).await {
-                    Ok(0) => break, // EOF
-                    Ok(_) => {
-                        if let Ok(request) = serde_json::from_str::<serde_json::Value>(&line) {
-                            if let Some(response) = handle_mcp_request(&server, request).await {
-                                if let Ok(response_json) = serde_json::to_string(&response) {
-                                    if writer.write_all(response_json.as_bytes()).await.is_err() ||
-                                       writer.write_all(b"\n").await.is_err() ||
-                                       writer.flush().await.is_err() {
-                                        break;
-                                    }
-                                } else {
-                                    tracing::error!("Failed to serialize JSON response");
-                                    break;
-                                }
-                            }
-                        } else {
-                            tracing::warn!("Failed to parse JSON request: {}", line.trim());
-                        }
-                    }
-                    Err(_) => break,
+/// Reloads configuration from the same sources as startup on every SIGHUP.
+///
+/// Only the fields listed in [`Config::with_reloadable_from`] are applied; changes
+/// to anything else are logged as needing a restart. A config that fails to load or
+/// validate is rejected and the running config is kept.
+#[cfg(unix)]
+async fn reload_on_sighup(
+    server: Arc<PolymarketMcpServer>,
+    config_path: Option<String>,
+    log_level_override: Option<String>,
+) {
+    let mut hangups = match signal::unix::signal(signal::unix::SignalKind::hangup()) {
+        Ok(hangups) => hangups,
+        Err(e) => {
+            tracing::warn!("Failed to install SIGHUP handler: {}", e);
+            return;
+        }
+    };
+
+    while hangups.recv().await.is_some() {
+        let loaded = match &config_path {
+            Some(config_path) => Config::load_with_file(config_path),
+            None => Config::load(),
+        };
+        let mut config = match loaded {
+            Ok(config) => config,
+            Err(e) => {
+                tracing::warn!("Config reload failed, keeping current config: {:#}", e);
+                continue;
+            }
+        };
+        if let Some(log_level) = &log_level_override {
+            config.logging.level.clone_from(log_level);
+        }
+
+        let ignored = server.reload_config(&config);
+        if !ignored.is_empty() {
+            tracing::warn!(
+                "Config changes in [{}] require a restart and were not applied",
+                ignored.join(", ")
+            );
+        }
+        tracing::info!("Configuration reloaded");
+    }
+}
+
+/// Accepts TCP connections and serves each one on its own task. Failing to accept
+/// one connection is logged and does not stop the server.
+async fn serve_tcp(server: Arc<PolymarketMcpServer>, listener: TcpListener) -> anyhow::Result<()> {
+    loop {
+        let (stream, peer) = match listener.accept().await {
+            Ok(accepted) => accepted,
+            Err(e) => {
+                tracing::warn!("Failed to accept MCP connection: {}", e);
+                tokio::time::sleep(ACCEPT_ERROR_BACKOFF).await;
+                continue;
+            }
+        };
+        tracing::info!("Accepted MCP connection from {}", peer);
+
+        let server = server.clone();
+        tokio::spawn(async move {
+            let (read_half, write_half) = stream.into_split();
+            serve_connection(server, AsyncBufReader::new(read_half), write_half).await;
+            tracing::info!("MCP connection from {} closed", peer);
+        });
+    }
+}
+
+/// Runs the newline-delimited JSON-RPC loop for a single client connection.
+///
+/// Responses and queued notifications are written from this loop only, so the two
+/// never interleave on the wire.
+async fn serve_connection<R, W>(server: Arc<PolymarketMcpServer>, reader: R, mut writer: W)
+where
+    R: AsyncBufRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let (notifier, mut notifications) = mpsc::channel(NOTIFICATION_BUFFER);
+    let session = Session::new(notifier);
+    let max_request_bytes = server.config().api.max_request_bytes;
+    let mut lines = LineReader::new(reader, max_request_bytes);
+
+    loop {
+        let message = tokio::select! {
+            line = lines.next_line() => match line {
+                Ok(Some(Line::Complete(line))) => match handle_line(&server, &session, &line).await {
+                    Some(response) => response,
+                    None => continue,
+                },
+                Ok(Some(Line::TooLong)) => {
+                    tracing::warn!("Rejected request line longer than {} bytes", max_request_bytes);
+                    error_response(
+                        None,
+                        RpcError::new(
+                            RpcError::PARSE_ERROR,
+                            format!("Parse error: request exceeds {max_request_bytes} bytes"),
+                        ),
+                    )
+                }
+                Ok(None) => {
+                    tracing::info!("Client closed its input; ending session");
+                    break;
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to read from client, ending session: {}", e);
+                    break;
+                }
+            },
+            Some(notification) = notifications.recv() => notification,
+        };
+
+        if !write_message(&mut writer, &message).await {
+            break;
+        }
+    }
+
+    server.end_session(&session).await;
+}
+
+/// A line read by [`LineReader`].
+enum Line {
+    /// A full line without its `\n` or `\r\n` terminator
+    Complete(String),
+    /// A line over the length cap; its bytes were discarded
+    TooLong,
+}
+
+/// Reads newline-delimited input like [`AsyncBufReadExt::lines`], but never buffers more
+/// than `max_bytes` of a single line.
+///
+/// An oversized line is reported once as [`Line::TooLong`] and the rest of it is skipped
+/// up to the next newline, so the following request is read normally. All state lives in
+/// the struct, making [`LineReader::next_line`] safe to cancel in `select!`.
+struct LineReader<R> {
+    reader: R,
+    buf: Vec<u8>,
+    max_bytes: usize,
+    discarding: bool,
+}
+
+impl<R: AsyncBufRead + Unpin> LineReader<R> {
+    fn new(reader: R, max_bytes: usize) -> Self {
+        Self {
+            reader,
+            buf: Vec::new(),
+            max_bytes,
+            discarding: false,
+        }
+    }
+
+    async fn next_line(&mut self) -> std::io::Result<Option<Line>> {
+        loop {
+            let available = self.reader.fill_buf().await?;
+            if available.is_empty() {
+                // EOF: a trailing line without a newline still counts
+                if self.discarding || self.buf.is_empty() {
+                    self.discarding = false;
+                    return Ok(None);
                 }
+                return self.take_line().map(Some);
+            }
+
+            let newline = available.iter().position(|&b| b == b'\n');
+            let chunk_len = newline.unwrap_or(available.len());
+            let consumed = newline.map_or(chunk_len, |i| i + 1);
+
+            if self.discarding {
+                self.discarding = newline.is_none();
+                self.reader.consume(consumed);
+                continue;
+            }
+
+            if self.buf.len() + chunk_len > self.max_bytes {
+                self.buf.clear();
+                self.discarding = newline.is_none();
+                self.reader.consume(consumed);
+                return Ok(Some(Line::TooLong));
+            }
+
+            self.buf.extend_from_slice(&available[..chunk_len]);
+            self.reader.consume(consumed);
+            if newline.is_some() {
+                return self.take_line().map(Some);
             }
-        } => {}
+        }
+    }
+
+    fn take_line(&mut self) -> std::io::Result<Line> {
+        let mut bytes = std::mem::take(&mut self.buf);
+        if bytes.last() == Some(&b'\r') {
+            bytes.pop();
+        }
+        String::from_utf8(bytes)
+            .map(Line::Complete)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+}
+
+/// Writes one newline-delimited JSON message, returning false if the connection is unusable.
+///
+/// Failures are logged with the stage that failed; a broken pipe here usually means the
+/// client closed its end of the connection.
+async fn write_message<W: AsyncWrite + Unpin>(writer: &mut W, message: &Value) -> bool {
+    let Ok(json) = serde_json::to_string(message) else {
+        tracing::error!("Failed to serialize JSON response");
+        return false;
+    };
+
+    let result = match writer.write_all(json.as_bytes()).await {
+        Ok(()) => match writer.write_all(b"\n").await {
+            Ok(()) => writer.flush().await.map_err(|e| ("flush", e)),
+            Err(e) => Err(("write newline", e)),
+        },
+        Err(e) => Err(("write message", e)),
+    };
+    if let Err((stage, e)) = result {
+        tracing::warn!("Output {} failed, ending session: {}", stage, e);
+        return false;
+    }
+    true
+}
+
+/// Parses one line of input and dispatches it, answering malformed JSON with a parse error.
+///
+/// A line holding a JSON array is a JSON-RPC batch: each element is handled in order
+/// and the responses are returned as an array, leaving out notifications.
+async fn handle_line(
+    server: &Arc<PolymarketMcpServer>,
+    session: &Session,
+    line: &str,
+) -> Option<Value> {
+    if line.trim().is_empty() {
+        return None;
+    }
+
+    match serde_json::from_str::<serde_json::Value>(line) {
+        Ok(Value::Array(requests)) => handle_batch(server, session, requests).await,
+        Ok(request) => handle_mcp_request(server, session, request).await,
+        Err(e) => {
+            tracing::warn!("Failed to parse JSON request: {} ({})", line.trim(), e);
+            Some(error_response(
+                None,
+                RpcError::new(RpcError::PARSE_ERROR, "Parse error"),
+            ))
+        }
+    }
+}
+
+async fn handle_batch(
+    server: &Arc<PolymarketMcpServer>,
+    session: &Session,
+    requests: Vec<Value>,
+) -> Option<Value> {
+    if requests.is_empty() {
+        return Some(error_response(
+            None,
+            RpcError::new(RpcError::INVALID_REQUEST, "Invalid Request: empty batch"),
+        ));
+    }
+
+    let mut responses = Vec::with_capacity(requests.len());
+    for request in requests {
+        if let Some(response) = handle_mcp_request(server, session, request).await {
+            responses.push(response);
+        }
+    }
+    // A batch of only notifications gets no reply at all
+    (!responses.is_empty()).then_some(Value::Array(responses))
+}
+
+/// JSON-RPC 2.0 error object returned in place of `result` when a request fails.
+#[derive(Debug, Serialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    data: Option<Value>,
+}
+
+type RpcResult<T> = std::result::Result<T, RpcError>;
+
+impl RpcError {
+    const PARSE_ERROR: i64 = -32700;
+    const INVALID_REQUEST: i64 = -32600;
+    const METHOD_NOT_FOUND: i64 = -32601;
+    const INVALID_PARAMS: i64 = -32602;
+    const INTERNAL_ERROR: i64 = -32603;
+
+    // Implementation-defined server errors (-32000 to -32099)
+    const API_ERROR: i64 = -32000;
+    const NETWORK_ERROR: i64 = -32001;
+    const DESERIALIZATION_ERROR: i64 = -32002;
+    const CONFIG_ERROR: i64 = -32003;
+    const NOT_FOUND: i64 = -32004;
+
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            data: None,
+        }
     }
 
-    Ok(())
+    fn invalid_params(message: impl Into<String>) -> Self {
+        Self::new(Self::INVALID_PARAMS, message)
+    }
+
+    fn with_data(mut self, data: Value) -> Self {
+        self.data = Some(data);
+        self
+    }
+}
+
+impl From<PolymarketError> for RpcError {
+    fn from(error: PolymarketError) -> Self {
+        let message = error.to_string();
+        match error {
+            PolymarketError::Api {
+                status_code,
+                request_id,
+                ..
+            } => Self::new(Self::API_ERROR, message).with_data(json!({
+                "status_code": status_code,
+                "request_id": request_id
+            })),
+            PolymarketError::NotFound { resource } => Self::new(Self::NOT_FOUND, message)
+                .with_data(json!({
+                    "resource": resource
+                })),
+            PolymarketError::Network { .. } => Self::new(Self::NETWORK_ERROR, message),
+            PolymarketError::Deserialization { .. } => {
+                Self::new(Self::DESERIALIZATION_ERROR, message)
+            }
+            PolymarketError::Config { .. } => Self::new(Self::CONFIG_ERROR, message),
+            // Per MCP, an unknown resource or prompt is an invalid-params error
+            PolymarketError::InvalidArgument { field, reason } => Self::invalid_params(message)
+                .with_data(json!({
+                    "field": field,
+                    "reason": reason
+                })),
+            PolymarketError::Resource { .. } => Self::new(Self::INVALID_PARAMS, message),
+            PolymarketError::Internal { .. } | PolymarketError::Io { .. } => {
+                Self::new(Self::INTERNAL_ERROR, message)
+            }
+        }
+    }
 }
 
 async fn handle_mcp_request(
     server: &Arc<PolymarketMcpServer>,
+    session: &Session,
     request: serde_json::Value,
 ) -> Option<serde_json::Value> {
-    let method = request.get("method")?.as_str()?;
     let id = request.get("id").cloned();
+    let Some(method) = request.get("method").and_then(|v| v.as_str()) else {
+        return Some(error_response(
+            id,
+            RpcError::new(RpcError::INVALID_REQUEST, "Invalid Request"),
+        ));
+    };
     let params = request
         .get("params")
         .cloned()
@@ -421,264 +1777,2397 @@ async fn handle_mcp_request(
         return None;
     }
 
+    let started = std::time::Instant::now();
+    let outcome = dispatch_request(server, session, method, &params).await;
+    // Tool failures are reported in the result rather than as JSON-RPC errors
+    let is_error = match &outcome {
+        Ok(result) => result.get("isError") == Some(&Value::Bool(true)),
+        Err(_) => true,
+    };
+    let request_id = id.clone().unwrap_or_default();
+    let tool = params.get("name").and_then(|name| name.as_str());
+    tracing::debug!(
+        method,
+        id = %request_id,
+        tool,
+        elapsed_ms = started.elapsed().as_secs_f64() * 1000.0,
+        is_error,
+        "Handled MCP request"
+    );
+
+    match outcome {
+        Ok(result) => Some(json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "result": result
+        })),
+        Err(error) => Some(error_response(id, error)),
+    }
+}
+
+fn error_response(id: Option<Value>, error: RpcError) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": error
+    })
+}
+
+async fn dispatch_request(
+    server: &Arc<PolymarketMcpServer>,
+    session: &Session,
+    method: &str,
+    params: &Value,
+) -> RpcResult<Value> {
     let result = match method {
         "initialize" => {
+            let version = negotiate_protocol_version(optional_str(params, "protocolVersion"));
+            let mut capabilities = json!({
+                "tools": { "listChanged": false },
+                "resources": { "subscribe": true, "listChanged": false },
+                "prompts": { "listChanged": false }
+            });
+            if server.config().server.completions {
+                capabilities["completions"] = json!({});
+            }
             json!({
-                "protocolVersion": "2024-11-05",
-                "capabilities": {
-                    "tools": {},
-                    "resources": {},
-                    "prompts": {}
-                },
+                "protocolVersion": version,
+                "capabilities": capabilities,
                 "serverInfo": {
                     "name": "polymarket-mcp",
                     "version": env!("CARGO_PKG_VERSION")
                 }
             })
         }
+        // Liveness check; clients may drop the connection if it goes unanswered
+        "ping" => json!({}),
         "tools/list" => {
-            json!({
-                "tools": [
-                    {
-                        "name": "get_active_markets",
-                        "description": "Get list of active prediction markets",
-                        "inputSchema": {
-                            "type": "object",
-                            "properties": {
-                                "limit": {
-                                    "type": "number",
-                                    "description": "Maximum number of markets to return"
-                                }
-                            }
-                        }
-                    },
-                    {
-                        "name": "get_market_details",
-                        "description": "Get detailed information about a specific market",
-                        "inputSchema": {
-                            "type": "object",
-                            "properties": {
-                                "market_id": {
-                                    "type": "string",
-                                    "description": "The ID of the market"
-                                }
-                            },
-                            "required": ["market_id"]
-                        }
-                    },
-                    {
-                        "name": "search_markets",
-                        "description": "Search markets by keyword",
-                        "inputSchema": {
-                            "type": "object",
-                            "properties": {
-                                "keyword": {
-                                    "type": "string",
-                                    "description": "Keyword to search for"
-                                },
-                                "limit": {
-                                    "type": "number",
-                                    "description": "Maximum number of results"
-                                }
-                            },
-                            "required": ["keyword"]
-                        }
-                    },
-                    {
-                        "name": "get_market_prices",
-                        "description": "Get current prices for a market",
-                        "inputSchema": {
-                            "type": "object",
-                            "properties": {
-                                "market_id": {
-                                    "type": "string",
-                                    "description": "The ID of the market"
-                                }
-                            },
-                            "required": ["market_id"]
-                        }
-                    },
-                    {
-                        "name": "get_trending_markets",
-                        "description": "Get trending markets with high volume",
-                        "inputSchema": {
-                            "type": "object",
-                            "properties": {
-                                "limit": {
-                                    "type": "number",
-                                    "description": "Maximum number of markets to return"
-                                }
-                            }
-                        }
-                    }
-                ]
-            })
+            json!({ "tools": tool_definitions() })
         }
-        "tools/call" => {
-            let name = params.get("name")?.as_str()?;
-            let arguments = params
-                .get("arguments")
-                .cloned()
-                .unwrap_or(serde_json::Value::Object(Default::default()));
-
-            match name {
-                "get_active_markets" => {
-                    let limit = arguments
-                        .get("limit")
-                        .and_then(|v| v.as_u64())
-                        .map(|l| l as u32);
-                    match server.get_active_markets(limit).await {
-                        Ok(result) => json!({
-                            "content": [{
-                                "type": "text",
-                                "text": serde_json::to_string_pretty(&result).unwrap()
-                            }]
-                        }),
-                        Err(e) => json!({
-                            "content": [{
-                                "type": "text",
-                                "text": format!("Error: {}", e)
-                            }],
-                            "isError": true
-                        }),
-                    }
-                }
-                "get_market_details" => {
-                    let market_id = arguments.get("market_id")?.as_str()?.to_string();
-                    match server.get_market_details(market_id).await {
-                        Ok(result) => json!({
-                            "content": [{
-                                "type": "text",
-                                "text": serde_json::to_string_pretty(&result).unwrap()
-                            }]
-                        }),
-                        Err(e) => json!({
-                            "content": [{
-                                "type": "text",
-                                "text": format!("Error: {}", e)
-                            }],
-                            "isError": true
-                        }),
-                    }
-                }
-                "search_markets" => {
-                    let keyword = arguments.get("keyword")?.as_str()?.to_string();
-                    let limit = arguments
-                        .get("limit")
-                        .and_then(|v| v.as_u64())
-                        .map(|l| l as u32);
-                    match server.search_markets(keyword, limit).await {
-                        Ok(result) => json!({
-                            "content": [{
-                                "type": "text",
-                                "text": serde_json::to_string_pretty(&result).unwrap()
-                            }]
-                        }),
-                        Err(e) => json!({
-                            "content": [{
-                                "type": "text",
-                                "text": format!("Error: {}", e)
-                            }],
-                            "isError": true
-                        }),
-                    }
-                }
-                "get_market_prices" => {
-                    let market_id = arguments.get("market_id")?.as_str()?.to_string();
-                    match server.get_market_prices(market_id).await {
-                        Ok(result) => json!({
-                            "content": [{
-                                "type": "text",
-                                "text": serde_json::to_string_pretty(&result).unwrap()
-                            }]
-                        }),
-                        Err(e) => json!({
-                            "content": [{
-                                "type": "text",
-                                "text": format!("Error: {}", e)
-                            }],
-                            "isError": true
-                        }),
-                    }
+        "tools/call" => call_tool(server, params).await?,
+        "resources/list" => server.list_resources().await?,
+        "resources/read" => {
+            let uri = required_str(params, "uri")?;
+            server.read_resource(uri).await?
+        }
+        "resources/subscribe" => {
+            let uri = required_str(params, "uri")?;
+            if !SUBSCRIBABLE_URIS.contains(&uri) {
+                return Err(RpcError::invalid_params(format!(
+                    "Subscriptions are supported for: {}",
+                    SUBSCRIBABLE_URIS.join(", ")
+                )));
+            }
+            server.subscribe(session, uri).await;
+            json!({})
+        }
+        "resources/unsubscribe" => {
+            let uri = required_str(params, "uri")?;
+            server.unsubscribe(session, uri).await;
+            json!({})
+        }
+        "prompts/list" => server.list_prompts().await?,
+        "prompts/get" => {
+            let name = required_str(params, "name")?;
+            let arguments = params.get("arguments").cloned();
+            server.get_prompt(name, arguments).await?
+        }
+        "completion/complete" if server.config().server.completions => {
+            let reference = params.get("ref").unwrap_or(&Value::Null);
+            let argument = params.get("argument").unwrap_or(&Value::Null);
+            let argument_name = required_str(argument, "name")?;
+            let value = optional_str(argument, "value").unwrap_or_default();
+            match optional_str(reference, "type") {
+                Some("ref/prompt") => {
+                    let prompt = required_str(reference, "name")?;
+                    server.complete(prompt, argument_name, value).await?
                 }
-                "get_trending_markets" => {
-                    let limit = arguments
-                        .get("limit")
-                        .and_then(|v| v.as_u64())
-                        .map(|l| l as u32);
-                    match server.get_trending_markets(limit).await {
-                        Ok(result) => json!({
-                            "content": [{
-                                "type": "text",
-                                "text": serde_json::to_string_pretty(&result).unwrap()
-                            }]
-                        }),
-                        Err(e) => json!({
-                            "content": [{
-                                "type": "text",
-                                "text": format!("Error: {}", e)
-                            }],
-                            "isError": true
-                        }),
-                    }
+                // Resource URIs are fixed, so there is nothing to complete
+                Some("ref/resource") => completion_result(Vec::new(), value),
+                _ => {
+                    return Err(RpcError::invalid_params(
+                        "ref.type must be ref/prompt or ref/resource",
+                    ));
                 }
-                _ => json!({
-                    "content": [{
-                        "type": "text",
-                        "text": format!("Unknown tool: {}", name)
-                    }],
-                    "isError": true
-                }),
             }
         }
-        "resources/list" => match server.list_resources().await {
-            Ok(result) => result,
-            Err(e) => json!({
-                "resources": [],
-                "error": format!("Error listing resources: {}", e)
-            }),
-        },
-        "resources/read" => {
-            let uri = params.get("uri")?.as_str()?;
-            match server.read_resource(uri).await {
-                Ok(result) => result,
-                Err(e) => json!({
-                    "contents": [],
-                    "error": format!("Error reading resource: {}", e)
-                }),
+        _ => {
+            return Err(RpcError::new(
+                RpcError::METHOD_NOT_FOUND,
+                "Method not found",
+            ));
+        }
+    };
+
+    Ok(result)
+}
+
+/// Loads the prompt templates in `server.prompts_dir`, if one is configured.
+fn load_prompt_templates(config: &Config) -> Result<Vec<PromptTemplate>> {
+    let Some(dir) = &config.server.prompts_dir else {
+        return Ok(Vec::new());
+    };
+    let templates = prompt_templates::load_dir(std::path::Path::new(dir))?;
+    tracing::info!("Loaded {} prompt templates from {}", templates.len(), dir);
+    Ok(templates)
+}
+
+/// Builds a `completion/complete` result from the `candidates` starting with
+/// `partial`, capped at [`MAX_COMPLETIONS`].
+fn completion_result(candidates: Vec<String>, partial: &str) -> Value {
+    let partial = partial.to_lowercase();
+    let matches: Vec<String> = candidates
+        .into_iter()
+        .filter(|candidate| candidate.to_lowercase().starts_with(&partial))
+        .collect();
+    let total = matches.len();
+    let values: Vec<String> = matches.into_iter().take(MAX_COMPLETIONS).collect();
+
+    json!({
+        "completion": {
+            "values": values,
+            "total": total,
+            "hasMore": total > MAX_COMPLETIONS
+        }
+    })
+}
+
+/// Runs a `tools/call` request and tags its result with a `request_id`.
+///
+/// The id is attached to every log line emitted while the tool runs. When the tool
+/// fails on an upstream API error, that error's own id is reported instead, so the id
+/// a user quotes matches the one in the logs either way.
+async fn call_tool(server: &Arc<PolymarketMcpServer>, params: &Value) -> RpcResult<Value> {
+    let name = required_str(params, "name")?;
+    let request_id = RequestId::new();
+    let span = tracing::info_span!("tool_call", tool = name, %request_id);
+    let result = run_tool(server, params, &request_id)
+        .instrument(span)
+        .await?;
+
+    if result["isError"] == true {
+        let request_id = result["request_id"].as_str().unwrap_or_default();
+        tracing::warn!(tool = name, request_id, "Tool call failed");
+    }
+    Ok(result)
+}
+
+async fn run_tool(
+    server: &Arc<PolymarketMcpServer>,
+    params: &Value,
+    request_id: &RequestId,
+) -> RpcResult<Value> {
+    let name = required_str(params, "name")?;
+    let arguments = params
+        .get("arguments")
+        .cloned()
+        .unwrap_or(serde_json::Value::Object(Default::default()));
+
+    // Only advertised tools are counted, so arbitrary names can't grow the map
+    if TOOL_NAMES.contains(&name) {
+        server.client.record_tool_call(name).await;
+    }
+
+    let result = match name {
+        "get_active_markets" => {
+            let limit = optional_u32(&arguments, "limit");
+            let sort_by = optional_str(&arguments, "sort_by")
+                .map(str::parse::<MarketSort>)
+                .transpose()
+                .map_err(RpcError::invalid_params)?
+                .unwrap_or_default();
+            let ascending = optional_bool(&arguments, "ascending").unwrap_or(false);
+            let include_archived = optional_bool(&arguments, "include_archived");
+            let format = output_format(&arguments)?;
+            format.markets_response(
+                server
+                    .get_active_markets(limit, sort_by, ascending, include_archived)
+                    .await,
+                request_id,
+            )
+        }
+        "get_market_details" => {
+            let market_id = required_str(&arguments, "market_id")?.to_string();
+            let include_book = optional_bool(&arguments, "include_book").unwrap_or(false);
+            tool_response(
+                server.get_market_details(market_id, include_book).await,
+                request_id,
+            )
+        }
+        "get_market_by_slug" => {
+            let slug = required_str(&arguments, "slug")?;
+            if !is_valid_slug(slug) {
+                return Err(PolymarketError::invalid_argument(
+                    "slug",
+                    "must contain only lowercase letters, digits and hyphens",
+                )
+                .into());
             }
+            tool_response(
+                server.get_market_by_slug(slug.to_string()).await,
+                request_id,
+            )
         }
-        "prompts/list" => match server.list_prompts().await {
-            Ok(result) => result,
-            Err(e) => json!({
-                "prompts": [],
-                "error": format!("Error listing prompts: {}", e)
-            }),
-        },
-        "prompts/get" => {
-            let name = params.get("name")?.as_str()?;
-            let arguments = params.get("arguments").cloned();
-            match server.get_prompt(name, arguments).await {
-                Ok(result) => result,
-                Err(e) => json!({
-                    "messages": [],
-                    "error": format!("Error getting prompt: {}", e)
-                }),
+        "resolve_market_url" => {
+            let url = required_str(&arguments, "url")?;
+            let target = market_url::parse(url).map_err(RpcError::invalid_params)?;
+            tool_response(
+                server.resolve_market_url(url.to_string(), target).await,
+                request_id,
+            )
+        }
+        "search_markets" => {
+            let keywords = required_keywords(&arguments, "keyword")?;
+            let keyword_mode = optional_str(&arguments, "mode")
+                .map(str::parse::<KeywordMode>)
+                .transpose()
+                .map_err(RpcError::invalid_params)?
+                .unwrap_or_default();
+            let limit = optional_u32(&arguments, "limit");
+            let match_mode = optional_str(&arguments, "match_mode")
+                .map(str::parse::<MatchMode>)
+                .transpose()
+                .map_err(RpcError::invalid_params)?
+                .unwrap_or_default();
+            let filters = SearchFilters {
+                min_liquidity: optional_f64(&arguments, "min_liquidity"),
+                max_liquidity: optional_f64(&arguments, "max_liquidity"),
+                min_volume: optional_f64(&arguments, "min_volume"),
+            };
+            filters.validate().map_err(RpcError::invalid_params)?;
+            let format = output_format(&arguments)?;
+            format.markets_response(
+                server
+                    .search_markets(keywords, keyword_mode, limit, match_mode, filters)
+                    .await,
+                request_id,
+            )
+        }
+        "export_markets_csv" => {
+            let keywords = match arguments.get("keyword") {
+                Some(_) => Some(required_keywords(&arguments, "keyword")?),
+                None => None,
+            };
+            let limit = optional_u32(&arguments, "limit");
+            text_tool_response(
+                server.export_markets_csv(keywords, limit).await,
+                &format!(
+                    "CSV export, one market per row after a header row: {}",
+                    export::CSV_COLUMNS.join(", ")
+                ),
+                request_id,
+            )
+        }
+        "get_market_prices" => {
+            let market_id = required_str(&arguments, "market_id")?.to_string();
+            tool_response(server.get_market_prices(market_id).await, request_id)
+        }
+        "get_market_outcomes" => {
+            let market_id = required_str(&arguments, "market_id")?.to_string();
+            tool_response(server.get_market_outcomes(market_id).await, request_id)
+        }
+        "get_trending_markets" => {
+            let limit = optional_u32(&arguments, "limit");
+            tool_response(server.get_trending_markets(limit).await, request_id)
+        }
+        "get_order_book" => {
+            let market_id = required_str(&arguments, "market_id")?.to_string();
+            let outcome_id = optional_str(&arguments, "outcome_id").map(str::to_string);
+            let depth_pct = optional_f64(&arguments, "depth_pct").unwrap_or(DEFAULT_DEPTH_PCT);
+            if !depth_pct.is_finite() || depth_pct <= 0.0 {
+                return Err(RpcError::invalid_params(format!(
+                    "depth_pct must be a positive number, got {depth_pct}"
+                )));
+            }
+            tool_response(
+                server
+                    .get_order_book(market_id, outcome_id, depth_pct)
+                    .await,
+                request_id,
+            )
+        }
+        "get_market_trades" => {
+            let market_id = required_str(&arguments, "market_id")?.to_string();
+            let limit = optional_u32(&arguments, "limit");
+            tool_response(server.get_market_trades(market_id, limit).await, request_id)
+        }
+        "get_market_stats" => {
+            let market_id = required_str(&arguments, "market_id")?.to_string();
+            tool_response(server.get_market_stats(market_id).await, request_id)
+        }
+        "list_categories" => tool_response(server.list_categories().await, request_id),
+        "get_notable_markets" => {
+            let limit = optional_u32(&arguments, "limit");
+            tool_response(server.get_notable_markets(limit).await, request_id)
+        }
+        "market_overview" => {
+            let sample_size = optional_u32(&arguments, "sample_size");
+            tool_response(server.market_overview(sample_size).await, request_id)
+        }
+        "get_markets_by_category" => {
+            let category = required_str(&arguments, "category")?.to_string();
+            let limit = optional_u32(&arguments, "limit");
+            tool_response(
+                server.get_markets_by_category(category, limit).await,
+                request_id,
+            )
+        }
+        "get_tag_markets" => {
+            // Tag ids are numeric; accept them as JSON numbers too
+            let tag_id = match arguments.get("tag_id") {
+                Some(Value::Number(id)) => id.to_string(),
+                _ => required_str(&arguments, "tag_id")?.trim().to_string(),
+            };
+            if tag_id.is_empty() || !tag_id.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(PolymarketError::invalid_argument(
+                    "tag_id",
+                    "must be a numeric Polymarket tag id",
+                )
+                .into());
+            }
+            let related_tags = optional_bool(&arguments, "related_tags");
+            let limit = optional_u32(&arguments, "limit");
+            tool_response(
+                server.get_tag_markets(tag_id, related_tags, limit).await,
+                request_id,
+            )
+        }
+        "get_market_sentiment" => {
+            let market_id = required_str(&arguments, "market_id")?.to_string();
+            tool_response(server.get_market_sentiment(market_id).await, request_id)
+        }
+        "get_price_history" => {
+            let market_id = required_str(&arguments, "market_id")?.to_string();
+            let interval = optional_str(&arguments, "interval").unwrap_or("1d");
+            if !PRICE_HISTORY_INTERVALS.contains(&interval) {
+                return Err(RpcError::invalid_params(format!(
+                    "interval must be one of: {}",
+                    PRICE_HISTORY_INTERVALS.join(", ")
+                )));
+            }
+            tool_response(
+                server
+                    .get_price_history(market_id, interval.to_string())
+                    .await,
+                request_id,
+            )
+        }
+        "find_arbitrage" => {
+            let keyword = required_str(&arguments, "keyword")?.to_string();
+            let limit = optional_u32(&arguments, "limit");
+            tool_response(server.find_arbitrage(keyword, limit).await, request_id)
+        }
+        "find_similar_markets" => {
+            let market_id = required_str(&arguments, "market_id")?.to_string();
+            let limit = optional_u32(&arguments, "limit");
+            tool_response(
+                server.find_similar_markets(market_id, limit).await,
+                request_id,
+            )
+        }
+        "get_positions" => {
+            let user_address = required_str(&arguments, "user_address")?;
+            if !is_valid_address(user_address) {
+                return Err(PolymarketError::invalid_argument(
+                    "user_address",
+                    "must be 0x followed by 40 hex characters",
+                )
+                .into());
+            }
+            tool_response(
+                server.get_positions(user_address.to_string()).await,
+                request_id,
+            )
+        }
+        "get_events" => {
+            let limit = optional_u32(&arguments, "limit");
+            let active = optional_bool(&arguments, "active");
+            tool_response(server.get_events(limit, active).await, request_id)
+        }
+        "get_event_markets" => {
+            let event_id = required_str(&arguments, "event_id")?.to_string();
+            tool_response(server.get_event_markets(event_id).await, request_id)
+        }
+        "get_markets_closing_soon" => {
+            let within_hours = optional_u32(&arguments, "within_hours").unwrap_or(24);
+            if within_hours == 0 {
+                return Err(RpcError::invalid_params(
+                    "within_hours must be a positive number of hours",
+                ));
+            }
+            if within_hours > MAX_CLOSING_WINDOW_HOURS {
+                return Err(PolymarketError::invalid_argument(
+                    "within_hours",
+                    format!("must be at most {MAX_CLOSING_WINDOW_HOURS} hours"),
+                )
+                .into());
+            }
+            let limit = optional_u32(&arguments, "limit");
+            tool_response(
+                server.get_markets_closing_soon(within_hours, limit).await,
+                request_id,
+            )
+        }
+        "get_new_markets" => {
+            let since = new_markets_since(&arguments, chrono::Utc::now())?;
+            let limit = optional_u32(&arguments, "limit");
+            tool_response(server.get_new_markets(since, limit).await, request_id)
+        }
+        "get_resolved_markets" => {
+            let limit = optional_u32(&arguments, "limit");
+            tool_response(server.get_resolved_markets(limit).await, request_id)
+        }
+        "get_markets_batch" => {
+            let market_ids = required_str_array(&arguments, "market_ids")?;
+            let max = server.config().api.max_limit as usize;
+            if market_ids.len() > max {
+                return Err(RpcError::invalid_params(format!(
+                    "market_ids accepts at most {max} ids"
+                )));
+            }
+            tool_response(server.get_markets_batch(market_ids).await, request_id)
+        }
+        "invalidate_cache" => {
+            let uri = optional_str(&arguments, "uri").map(str::to_string);
+            let market_id = optional_str(&arguments, "market_id").map(str::to_string);
+            tool_response(server.invalidate_cache(uri, market_id).await, request_id)
+        }
+        "set_log_level" => {
+            let level = required_str(&arguments, "level")?.to_lowercase();
+            if !LOG_LEVELS.contains(&level.as_str()) {
+                return Err(RpcError::invalid_params(format!(
+                    "Invalid level '{level}'. Valid levels: {}",
+                    LOG_LEVELS.join(", ")
+                )));
             }
+            tool_response(server.set_log_level(&level).await, request_id)
         }
+        "get_metrics" => tool_response(server.get_metrics().await, request_id),
+        "health" => tool_response(server.health().await, request_id),
         _ => {
-            json!({
-                "error": {
-                    "code": -32601,
-                    "message": "Method not found"
-                }
-            })
+            return Err(RpcError::invalid_params(format!("Unknown tool: {name}")));
         }
     };
 
-    Some(json!({
-        "jsonrpc": "2.0",
-        "id": id,
-        "result": result
-    }))
+    Ok(result)
+}
+
+/// MCP protocol revisions this server speaks, newest first.
+const SUPPORTED_PROTOCOL_VERSIONS: &[&str] = &["2025-03-26", "2024-11-05"];
+
+/// Echoes the client's protocol version when supported, otherwise offers our latest.
+fn negotiate_protocol_version(requested: Option<&str>) -> &'static str {
+    requested
+        .and_then(|version| {
+            SUPPORTED_PROTOCOL_VERSIONS
+                .iter()
+                .find(|supported| **supported == version)
+        })
+        .copied()
+        .unwrap_or(SUPPORTED_PROTOCOL_VERSIONS[0])
+}
+
+/// Reads a required string argument, failing with an invalid-params error.
+fn required_str<'a>(args: &'a Value, field: &str) -> RpcResult<&'a str> {
+    args.get(field)
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| PolymarketError::invalid_argument(field, "argument is required").into())
+}
+
+fn required_str_array(args: &Value, field: &str) -> RpcResult<Vec<String>> {
+    let items = args
+        .get(field)
+        .and_then(|v| v.as_array())
+        .filter(|items| !items.is_empty())
+        .ok_or_else(|| {
+            PolymarketError::invalid_argument(field, "argument must be a non-empty array")
+        })?;
+    items
+        .iter()
+        .map(|item| {
+            item.as_str().map(str::to_string).ok_or_else(|| {
+                PolymarketError::invalid_argument(field, "argument must contain only strings")
+                    .into()
+            })
+        })
+        .collect()
+}
+
+/// Reads an argument given either as one string or as a non-empty array of strings.
+fn required_keywords(args: &Value, field: &str) -> RpcResult<Vec<String>> {
+    match args.get(field) {
+        Some(Value::String(keyword)) => Ok(vec![keyword.clone()]),
+        Some(Value::Array(_)) => required_str_array(args, field),
+        _ => Err(PolymarketError::invalid_argument(
+            field,
+            "argument is required as a string or array of strings",
+        )
+        .into()),
+    }
+}
+
+fn optional_str<'a>(args: &'a Value, field: &str) -> Option<&'a str> {
+    args.get(field).and_then(|v| v.as_str())
+}
+
+/// Reads a non-negative integer argument, saturating values too large for a `u32`.
+///
+/// Negative or non-integer values are treated as absent.
+fn optional_u32(args: &Value, field: &str) -> Option<u32> {
+    args.get(field)
+        .and_then(|v| v.as_u64())
+        .map(|v| u32::try_from(v).unwrap_or(u32::MAX))
+}
+
+fn optional_f64(args: &Value, field: &str) -> Option<f64> {
+    args.get(field).and_then(|v| v.as_f64())
+}
+
+fn optional_bool(args: &Value, field: &str) -> Option<bool> {
+    args.get(field).and_then(|v| v.as_bool())
+}
+
+/// Resolves `get_new_markets`' lower bound from either an RFC3339 `since` or a
+/// positive `hours_ago`, defaulting to the last 24 hours.
+fn new_markets_since(
+    args: &Value,
+    now: chrono::DateTime<chrono::Utc>,
+) -> RpcResult<chrono::DateTime<chrono::Utc>> {
+    let since = optional_str(args, "since");
+    let hours_ago = optional_u32(args, "hours_ago");
+    match (since, hours_ago) {
+        (Some(_), Some(_)) => Err(RpcError::invalid_params(
+            "Pass either since or hours_ago, not both",
+        )),
+        (Some(since), None) => {
+            let since = chrono::DateTime::parse_from_rfc3339(since)
+                .map_err(|e| {
+                    RpcError::invalid_params(format!(
+                        "Invalid since '{since}': expected an RFC3339 timestamp such as 2024-06-01T00:00:00Z ({e})"
+                    ))
+                })?
+                .with_timezone(&chrono::Utc);
+            if since > now {
+                return Err(RpcError::invalid_params(format!(
+                    "since must not be in the future (got {})",
+                    since.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+                )));
+            }
+            Ok(since)
+        }
+        (None, Some(0)) => Err(RpcError::invalid_params(
+            "hours_ago must be a positive number of hours",
+        )),
+        (None, hours_ago) => {
+            let hours_ago = hours_ago.unwrap_or(24);
+            now.checked_sub_signed(chrono::Duration::hours(i64::from(hours_ago)))
+                .ok_or_else(|| {
+                    PolymarketError::invalid_argument(
+                        "hours_ago",
+                        format!("{hours_ago} hours is too far in the past"),
+                    )
+                    .into()
+                })
+        }
+    }
+}
+
+/// Wraps a tool handler result in the MCP `tools/call` content shape, tagged with
+/// the call's `request_id`.
+///
+/// Upstream API errors report their own id instead (see [`tool_error_payload`]).
+fn tool_response(result: Result<Value>, request_id: &RequestId) -> Value {
+    match result {
+        Ok(result) => json!({
+            "content": [{
+                "type": "text",
+                "text": serde_json::to_string_pretty(&result).unwrap_or_default()
+            }],
+            "request_id": request_id
+        }),
+        Err(e) => {
+            let payload = tool_error_payload(&e, request_id);
+            json!({
+                "content": [
+                    {
+                        "type": "text",
+                        "text": format!("Error: {}", e)
+                    },
+                    {
+                        "type": "text",
+                        "text": serde_json::to_string_pretty(&payload).unwrap_or_default()
+                    }
+                ],
+                "isError": true,
+                "request_id": payload["error"]["request_id"]
+            })
+        }
+    }
+}
+
+/// Describes a failed tool call for clients that want to act on the failure.
+///
+/// An upstream API error reports its own `request_id`, matching the one in the
+/// client's logs; other errors report `request_id`, the id of the call.
+fn tool_error_payload(error: &PolymarketError, request_id: &RequestId) -> Value {
+    json!({
+        "error": {
+            "error_type": error.error_type(),
+            "message": error.to_string(),
+            "status_code": error.status_code(),
+            "request_id": error.request_id().unwrap_or(request_id),
+            "attempts": error.retry_stats().map(|stats| stats.attempts),
+            "total_elapsed_ms": error.retry_stats().map(|stats| stats.total_elapsed_ms)
+        }
+    })
+}
+
+/// Describes top-of-book spreads and recent trades for the `analyze_market` prompt.
+///
+/// `trades` is `None` when they could not be fetched; either section then says its
+/// data is unavailable instead of being left out silently.
+fn market_activity_text(books: &[TopOfBook], trades: Option<&[Trade]>) -> String {
+    let quoted: Vec<String> = books
+        .iter()
+        .filter(|book| book.best_bid.is_some() || book.best_ask.is_some())
+        .map(|book| {
+            let price = |p: Option<f64>| p.map_or_else(|| "-".to_string(), |p| format!("{p:.3}"));
+            let spread = match (book.best_bid, book.best_ask) {
+                (Some(bid), Some(ask)) => format!(" (spread {:.3})", ask - bid),
+                _ => String::new(),
+            };
+            format!(
+                "- {}: bid {} / ask {}{}",
+                book.outcome,
+                price(book.best_bid),
+                price(book.best_ask),
+                spread
+            )
+        })
+        .collect();
+    let book_text = if quoted.is_empty() {
+        "Order book data unavailable for this market.".to_string()
+    } else {
+        quoted.join("\n")
+    };
+
+    let trades_text = match trades {
+        None => "Recent trades unavailable.".to_string(),
+        Some([]) => "No recent trades.".to_string(),
+        Some(trades) => trades
+            .iter()
+            .map(|trade| {
+                format!(
+                    "- {}: {} {:.2} {} @ {:.3}",
+                    trade.timestamp,
+                    trade.side.to_uppercase(),
+                    trade.size,
+                    trade.outcome_id,
+                    trade.price
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+    };
+
+    format!("Top of Book:\n{book_text}\n\nRecent Trades:\n{trades_text}")
+}
+
+/// Builds the `portfolio_summary` prompt body from a wallet's positions and the
+/// markets they belong to.
+fn portfolio_summary_text(
+    user_address: &str,
+    positions: &[Position],
+    markets: &[Market],
+) -> String {
+    let questions: HashMap<&str, &str> = markets
+        .iter()
+        .filter_map(|m| Some((m.condition_id.as_deref()?, m.question.as_str())))
+        .collect();
+    let question = |p: &Position| {
+        questions
+            .get(p.market_id.as_str())
+            .map_or_else(|| p.market_id.clone(), |q| q.to_string())
+    };
+
+    let total_value: f64 = positions.iter().map(|p| p.value).sum();
+    let total_cost: f64 = positions.iter().map(|p| p.cost_basis).sum();
+    let total_pnl: f64 = positions.iter().map(|p| p.unrealized_pnl).sum();
+
+    let mut by_pnl: Vec<&Position> = positions.iter().collect();
+    by_pnl.sort_by(|a, b| b.unrealized_pnl.total_cmp(&a.unrealized_pnl));
+    let describe = |p: &&Position| {
+        format!(
+            "- {} ({}): {:.2} shares, value ${:.2}, cost ${:.2}, PnL ${:.2}",
+            question(p),
+            p.outcome_id,
+            p.shares,
+            p.value,
+            p.cost_basis,
+            p.unrealized_pnl
+        )
+    };
+    let winners: Vec<String> = by_pnl.iter().take(3).map(describe).collect();
+    let losers: Vec<String> = by_pnl.iter().rev().take(3).map(describe).collect();
+
+    let largest = positions
+        .iter()
+        .max_by(|a, b| a.value.total_cmp(&b.value))
+        .map(|p| {
+            let share = if total_value > 0.0 {
+                p.value / total_value * 100.0
+            } else {
+                0.0
+            };
+            format!(
+                "{} ({}) at {:.1}% of portfolio value",
+                question(p),
+                p.outcome_id,
+                share
+            )
+        })
+        .unwrap_or_default();
+
+    let all: Vec<String> = positions.iter().map(|p| describe(&p)).collect();
+
+    format!(
+        "Summarize this Polymarket portfolio:\n\nWallet: {}\nPositions: {}\nTotal value: ${:.2}\nTotal cost basis: ${:.2}\nUnrealized PnL: ${:.2}\nLargest position: {}\n\nBiggest winners:\n{}\n\nBiggest losers:\n{}\n\nAll positions:\n{}\n\nSummarize:\n1. Total exposure and overall performance\n2. Biggest winners and losers by unrealized PnL\n3. Concentration risk across markets and outcomes\n4. Positions worth reviewing",
+        user_address,
+        positions.len(),
+        total_value,
+        total_cost,
+        total_pnl,
+        largest,
+        winners.join("\n"),
+        losers.join("\n"),
+        all.join("\n")
+    )
+}
+
+/// Builds the `get_market_prices` payload, annotating each outcome with its price
+/// normalized by the book total so any overround (or underround) is explicit.
+fn price_response(market_id: &str, prices: &[MarketPrice]) -> Value {
+    let total: f64 = prices.iter().map(|p| p.price).sum();
+    let outcomes: Vec<Value> = prices
+        .iter()
+        .map(|p| {
+            let mut entry = serde_json::to_value(p).unwrap_or_default();
+            let implied = if total > 0.0 { p.price / total } else { 0.0 };
+            entry["implied_probability"] = json!(implied);
+            entry
+        })
+        .collect();
+
+    json!({
+        "market_id": market_id,
+        "prices": outcomes,
+        "total_implied_probability": total,
+        "overround": total - 1.0
+    })
+}
+
+/// Rounds every number stored under one of [`PRICE_FIELDS`], at any depth of `value`.
+fn round_price_fields(value: &mut Value, decimals: u32) {
+    match value {
+        Value::Object(fields) => {
+            for (key, field) in fields.iter_mut() {
+                match field.as_f64() {
+                    Some(number) if PRICE_FIELDS.contains(&key.as_str()) => {
+                        *field = json!(round_to(number, decimals));
+                    }
+                    _ => round_price_fields(field, decimals),
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                round_price_fields(item, decimals);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn round_to(number: f64, decimals: u32) -> f64 {
+    let scale = 10f64.powi(decimals as i32);
+    (number * scale).round() / scale
+}
+
+/// How market-listing tools render their results.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum OutputFormat {
+    /// One pretty-printed JSON object holding the markets and request metadata
+    #[default]
+    Json,
+    /// One compact JSON object per market, newline-delimited, for stream parsing
+    Ndjson,
+}
+
+impl std::str::FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(Self::Json),
+            "ndjson" => Ok(Self::Ndjson),
+            other => Err(format!("Invalid format '{other}': expected json or ndjson")),
+        }
+    }
+}
+
+impl OutputFormat {
+    /// Renders a handler result whose `markets` field holds the listing.
+    fn markets_response(self, result: Result<Value>, request_id: &RequestId) -> Value {
+        match (self, result) {
+            (Self::Ndjson, Ok(result)) => {
+                let markets = result["markets"].as_array().map_or(&[][..], Vec::as_slice);
+                let lines: Vec<String> = markets.iter().map(Value::to_string).collect();
+                text_tool_response(
+                    Ok(lines.join("\n")),
+                    &format!("NDJSON, one market per line ({} markets)", lines.len()),
+                    request_id,
+                )
+            }
+            (_, result) => tool_response(result, request_id),
+        }
+    }
+}
+
+fn output_format(args: &Value) -> RpcResult<OutputFormat> {
+    optional_str(args, "format")
+        .map(str::parse::<OutputFormat>)
+        .transpose()
+        .map_err(RpcError::invalid_params)
+        .map(Option::unwrap_or_default)
+}
+
+/// Like [`tool_response`], but returns `text` verbatim after a short `note` describing
+/// its format, for output that is not JSON.
+fn text_tool_response(result: Result<String>, note: &str, request_id: &RequestId) -> Value {
+    match result {
+        Ok(text) => json!({
+            "content": [
+                {"type": "text", "text": note},
+                {"type": "text", "text": text}
+            ],
+            "request_id": request_id
+        }),
+        Err(e) => tool_response(Err(e), request_id),
+    }
+}
+
+/// Names of the tools in [`tool_definitions`], in the same order.
+const TOOL_NAMES: &[&str] = &[
+    "get_active_markets",
+    "get_market_details",
+    "get_market_by_slug",
+    "resolve_market_url",
+    "search_markets",
+    "export_markets_csv",
+    "get_market_prices",
+    "get_market_outcomes",
+    "get_trending_markets",
+    "get_order_book",
+    "get_market_trades",
+    "get_market_stats",
+    "list_categories",
+    "get_notable_markets",
+    "market_overview",
+    "get_markets_by_category",
+    "get_tag_markets",
+    "get_market_sentiment",
+    "get_price_history",
+    "find_arbitrage",
+    "find_similar_markets",
+    "get_positions",
+    "get_events",
+    "get_event_markets",
+    "get_markets_closing_soon",
+    "get_new_markets",
+    "get_resolved_markets",
+    "get_markets_batch",
+    "invalidate_cache",
+    "set_log_level",
+    "get_metrics",
+    "health",
+];
+
+/// Tool definitions advertised by `tools/list`.
+fn tool_definitions() -> Vec<Value> {
+    vec![
+        json!({
+            "name": "get_active_markets",
+            "description": "Get list of active prediction markets",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "limit": {
+                        "type": "number",
+                        "description": "Maximum number of markets to return"
+                    },
+                    "sort_by": {
+                        "type": "string",
+                        "enum": ["liquidity", "volume", "volume_24hr", "end_date"],
+                        "description": "Field to sort by (default: liquidity)"
+                    },
+                    "ascending": {
+                        "type": "boolean",
+                        "description": "Sort ascending instead of descending (default: false)"
+                    },
+                    "include_archived": {
+                        "type": "boolean",
+                        "description": "Also return archived markets (default: server configuration, normally false)"
+                    },
+                    "format": {
+                        "type": "string",
+                        "enum": ["json", "ndjson"],
+                        "description": "json (default): one JSON object; ndjson: one market per line"
+                    }
+                }
+            }
+        }),
+        json!({
+            "name": "get_market_details",
+            "description": "Get detailed information about a specific market",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "market_id": {
+                        "type": "string",
+                        "description": "The ID of the market"
+                    },
+                    "include_book": {
+                        "type": "boolean",
+                        "description": "Add best bid, best ask and mid for each outcome from the order book (default: false)"
+                    }
+                },
+                "required": ["market_id"]
+            }
+        }),
+        json!({
+            "name": "get_market_by_slug",
+            "description": "Get a market by the slug from its polymarket.com URL",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "slug": {
+                        "type": "string",
+                        "description": "Market slug, e.g. will-bitcoin-reach-100k-in-2024"
+                    }
+                },
+                "required": ["slug"]
+            }
+        }),
+        json!({
+            "name": "resolve_market_url",
+            "description": "Get the market(s) behind a polymarket.com event or market link",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "url": {
+                        "type": "string",
+                        "description": "Link such as https://polymarket.com/event/<slug> or https://polymarket.com/market/<slug>"
+                    }
+                },
+                "required": ["url"]
+            }
+        }),
+        json!({
+            "name": "search_markets",
+            "description": "Search markets by keyword",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "keyword": {
+                        "oneOf": [
+                            {"type": "string"},
+                            {"type": "array", "items": {"type": "string"}, "minItems": 1}
+                        ],
+                        "description": "Keyword to search for, or several keywords combined by mode"
+                    },
+                    "mode": {
+                        "type": "string",
+                        "enum": ["any", "all"],
+                        "description": "With several keywords: any (default) matches markets containing at least one, all requires every keyword"
+                    },
+                    "limit": {
+                        "type": "number",
+                        "description": "Maximum number of results"
+                    },
+                    "match_mode": {
+                        "type": "string",
+                        "enum": ["contains", "all_tokens", "fuzzy"],
+                        "description": "contains (default): whole keyword as substring; all_tokens: every word must appear; fuzzy: like all_tokens but tolerates typos"
+                    },
+                    "format": {
+                        "type": "string",
+                        "enum": ["json", "ndjson"],
+                        "description": "json (default): one JSON object; ndjson: one market per line"
+                    },
+                    "min_liquidity": {
+                        "type": "number",
+                        "description": "Only scan markets with at least this much liquidity (USD)"
+                    },
+                    "max_liquidity": {
+                        "type": "number",
+                        "description": "Only scan markets with at most this much liquidity (USD)"
+                    },
+                    "min_volume": {
+                        "type": "number",
+                        "description": "Only scan markets with at least this much total volume (USD)"
+                    }
+                },
+                "required": ["keyword"]
+            }
+        }),
+        json!({
+            "name": "export_markets_csv",
+            "description": "Export active markets, or markets matching a keyword search, as CSV for spreadsheets. Columns: id, slug, question, category, liquidity, volume, volume_24hr, end_date, favorite_outcome, favorite_price",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "keyword": {
+                        "oneOf": [
+                            {"type": "string"},
+                            {"type": "array", "items": {"type": "string"}, "minItems": 1}
+                        ],
+                        "description": "Export markets matching any of these keywords instead of the most liquid active markets"
+                    },
+                    "limit": {
+                        "type": "number",
+                        "description": "Maximum number of markets to export (default: 50)"
+                    }
+                }
+            }
+        }),
+        json!({
+            "name": "get_market_prices",
+            "description": "Get current prices for a market",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "market_id": {
+                        "type": "string",
+                        "description": "The ID of the market"
+                    }
+                },
+                "required": ["market_id"]
+            }
+        }),
+        json!({
+            "name": "get_market_outcomes",
+            "description": "Get each outcome of a market with its index, price and implied probability",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "market_id": {
+                        "type": "string",
+                        "description": "The ID of the market"
+                    }
+                },
+                "required": ["market_id"]
+            }
+        }),
+        json!({
+            "name": "get_trending_markets",
+            "description": "Get trending markets with high volume",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "limit": {
+                        "type": "number",
+                        "description": "Maximum number of markets to return"
+                    }
+                }
+            }
+        }),
+        json!({
+            "name": "get_order_book",
+            "description": "Get the full order book depth for one outcome of a market",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "market_id": {
+                        "type": "string",
+                        "description": "The ID of the market"
+                    },
+                    "outcome_id": {
+                        "type": "string",
+                        "description": "Outcome name or CLOB token id (default: first outcome)"
+                    },
+                    "depth_pct": {
+                        "type": "number",
+                        "description": "Report total size within this percent of the mid price (default: 2)"
+                    }
+                },
+                "required": ["market_id"]
+            }
+        }),
+        json!({
+            "name": "get_market_trades",
+            "description": "Get recent trades for a market with side, size, price, and trader address",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "market_id": {
+                        "type": "string",
+                        "description": "The ID of the market"
+                    },
+                    "limit": {
+                        "type": "number",
+                        "description": "Maximum number of trades to return (default: 50)"
+                    }
+                },
+                "required": ["market_id"]
+            }
+        }),
+        json!({
+            "name": "get_market_stats",
+            "description": "Get 24h market statistics: volume, price change, high/low, liquidity, and trader count",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "market_id": {
+                        "type": "string",
+                        "description": "The ID of the market"
+                    }
+                },
+                "required": ["market_id"]
+            }
+        }),
+        json!({
+            "name": "list_categories",
+            "description": "List categories of active markets with the number of markets in each",
+            "inputSchema": {
+                "type": "object",
+                "properties": {}
+            }
+        }),
+        json!({
+            "name": "get_notable_markets",
+            "description": "Get the most notable active markets right now, scored by 24h volume surge, liquidity and time to close rather than sheer size. Each result includes its score and score components",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "limit": {
+                        "type": "number",
+                        "description": "Maximum number of markets to return (default: 10)"
+                    }
+                }
+            }
+        }),
+        json!({
+            "name": "market_overview",
+            "description": "Get aggregate statistics over a sample of the most liquid active markets: count, total liquidity, total 24h volume, median liquidity, and top category",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "sample_size": {
+                        "type": "number",
+                        "description": "Number of active markets to aggregate (default: 200)"
+                    }
+                }
+            }
+        }),
+        json!({
+            "name": "get_markets_by_category",
+            "description": "Get active markets in a category, filtered by Polymarket tag where known",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "category": {
+                        "type": "string",
+                        "description": "Category name (e.g. Politics, Sports, Crypto)"
+                    },
+                    "limit": {
+                        "type": "number",
+                        "description": "Maximum number of markets to return (default: 20)"
+                    }
+                },
+                "required": ["category"]
+            }
+        }),
+        json!({
+            "name": "get_tag_markets",
+            "description": "Get active markets carrying a Polymarket tag id, as grouped on the site. Returns the query string sent upstream",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "tag_id": {
+                        "type": "string",
+                        "description": "Numeric Polymarket tag id (e.g. 2 for Politics)"
+                    },
+                    "related_tags": {
+                        "type": "boolean",
+                        "description": "Also include markets under related tags"
+                    },
+                    "limit": {
+                        "type": "number",
+                        "description": "Maximum number of markets to return (default: 20)"
+                    }
+                },
+                "required": ["tag_id"]
+            }
+        }),
+        json!({
+            "name": "get_market_sentiment",
+            "description": "Get deterministic signals for a market: favorite outcome and probability, liquidity tier, and bid/ask spread",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "market_id": {
+                        "type": "string",
+                        "description": "The ID of the market"
+                    }
+                },
+                "required": ["market_id"]
+            }
+        }),
+        json!({
+            "name": "get_price_history",
+            "description": "Get the price time series for each outcome of a market",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "market_id": {
+                        "type": "string",
+                        "description": "The ID of the market"
+                    },
+                    "interval": {
+                        "type": "string",
+                        "enum": ["1h", "6h", "1d", "1w", "max"],
+                        "description": "Time range to cover (default: 1d)"
+                    }
+                },
+                "required": ["market_id"]
+            }
+        }),
+        json!({
+            "name": "find_arbitrage",
+            "description": "Find markets whose outcome prices sum below 1.0 or equivalent markets priced apart",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "keyword": {
+                        "type": "string",
+                        "description": "Keyword selecting the related markets to compare"
+                    },
+                    "limit": {
+                        "type": "number",
+                        "description": "Maximum number of markets to compare (default: 20)"
+                    }
+                },
+                "required": ["keyword"]
+            }
+        }),
+        json!({
+            "name": "find_similar_markets",
+            "description": "Find active markets related to a market by shared question terms, event, or category, for hedging or baskets",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "market_id": {
+                        "type": "string",
+                        "description": "The ID of the market to find related markets for"
+                    },
+                    "limit": {
+                        "type": "number",
+                        "description": "Maximum number of similar markets to return (default: 10)"
+                    }
+                },
+                "required": ["market_id"]
+            }
+        }),
+        json!({
+            "name": "get_positions",
+            "description": "Get a wallet's positions with shares, value, cost basis, and unrealized PnL",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "user_address": {
+                        "type": "string",
+                        "description": "Wallet address (0x followed by 40 hex characters)"
+                    }
+                },
+                "required": ["user_address"]
+            }
+        }),
+        json!({
+            "name": "get_events",
+            "description": "List events, which group related markets (e.g. every candidate in an election)",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "limit": {
+                        "type": "number",
+                        "description": "Maximum number of events to return (default: 20)"
+                    },
+                    "active": {
+                        "type": "boolean",
+                        "description": "Only return active (true) or closed (false) events"
+                    }
+                }
+            }
+        }),
+        json!({
+            "name": "get_event_markets",
+            "description": "Get every market grouped under an event so related outcomes can be analyzed together",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "event_id": {
+                        "type": "string",
+                        "description": "The ID of the event"
+                    }
+                },
+                "required": ["event_id"]
+            }
+        }),
+        json!({
+            "name": "get_markets_closing_soon",
+            "description": "Get active markets whose end date falls within the next N hours, soonest first",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "within_hours": {
+                        "type": "number",
+                        "description": "Size of the window in hours (default: 24, max: 87840)"
+                    },
+                    "limit": {
+                        "type": "number",
+                        "description": "Maximum number of markets to return"
+                    }
+                }
+            }
+        }),
+        json!({
+            "name": "get_new_markets",
+            "description": "Get markets that started after a point in time, newest first. Useful for polling for newly listed markets",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "since": {
+                        "type": "string",
+                        "description": "RFC3339 timestamp, e.g. 2024-06-01T00:00:00Z"
+                    },
+                    "hours_ago": {
+                        "type": "number",
+                        "description": "Alternative to since: look back this many hours (default: 24)"
+                    },
+                    "limit": {
+                        "type": "number",
+                        "description": "Maximum number of markets to return"
+                    }
+                }
+            }
+        }),
+        json!({
+            "name": "get_resolved_markets",
+            "description": "Get recently resolved (closed) markets with their final outcome prices, most recent first",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "limit": {
+                        "type": "number",
+                        "description": "Maximum number of markets to return"
+                    }
+                }
+            }
+        }),
+        json!({
+            "name": "get_markets_batch",
+            "description": "Fetch several markets by ID at once, reporting any IDs that could not be fetched",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "market_ids": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "IDs of the markets to fetch"
+                    }
+                },
+                "required": ["market_ids"]
+            }
+        }),
+        json!({
+            "name": "invalidate_cache",
+            "description": "Drop cached data so it is refetched. Clears every cache when no argument is given",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "uri": {
+                        "type": "string",
+                        "description": "Resource URI to invalidate (e.g. market:123, markets:active)"
+                    },
+                    "market_id": {
+                        "type": "string",
+                        "description": "Market ID to invalidate"
+                    }
+                }
+            }
+        }),
+        json!({
+            "name": "set_log_level",
+            "description": "Change the server's log level at runtime without restarting. Returns the previous level",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "level": {
+                        "type": "string",
+                        "enum": LOG_LEVELS,
+                        "description": "New log level"
+                    }
+                },
+                "required": ["level"]
+            }
+        }),
+        json!({
+            "name": "get_metrics",
+            "description": "Get server metrics: API request/failure counts, cache hit ratio, average response time, and circuit breaker state",
+            "inputSchema": {
+                "type": "object",
+                "properties": {}
+            }
+        }),
+        json!({
+            "name": "health",
+            "description": "Check connectivity to the Polymarket API and report request latency",
+            "inputSchema": {
+                "type": "object",
+                "properties": {}
+            }
+        }),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_server() -> Arc<PolymarketMcpServer> {
+        let mut config = Config::default();
+        config.api.base_url = "http://127.0.0.1:9".to_string();
+        config.api.max_retries = 1;
+        config.cache.enabled = false;
+        Arc::new(PolymarketMcpServer::with_config(config).unwrap())
+    }
+
+    fn test_session() -> Session {
+        Session::new(mpsc::channel(NOTIFICATION_BUFFER).0)
+    }
+
+    #[tokio::test]
+    async fn test_malformed_line_returns_parse_error() {
+        let server = test_server();
+        let input: &[u8] =
+            b"\x00{not json]]\n{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"tools/list\"}\n";
+        let mut output = Vec::new();
+
+        serve_connection(server, input, &mut output).await;
+
+        let output = String::from_utf8(output).unwrap();
+        let responses: Vec<Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(responses.len(), 2);
+        assert_eq!(responses[0]["error"]["code"], RpcError::PARSE_ERROR);
+        assert!(responses[0]["id"].is_null());
+        assert!(responses[1]["result"]["tools"].is_array());
+    }
+
+    #[tokio::test]
+    async fn test_oversized_line_is_rejected_and_reading_resyncs() {
+        let mut config = Config::default();
+        config.api.base_url = "http://127.0.0.1:9".to_string();
+        config.api.max_request_bytes = 64;
+        let server = Arc::new(PolymarketMcpServer::with_config(config).unwrap());
+        let input = format!(
+            "{}\n{{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"ping\"}}\n",
+            "x".repeat(1000)
+        );
+        // A tiny read buffer forces the oversized line to span many fills
+        let reader = AsyncBufReader::with_capacity(16, input.as_bytes());
+        let mut output = Vec::new();
+
+        serve_connection(server, reader, &mut output).await;
+
+        let output = String::from_utf8(output).unwrap();
+        let responses: Vec<Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(responses.len(), 2);
+        assert_eq!(responses[0]["error"]["code"], RpcError::PARSE_ERROR);
+        assert_eq!(responses[1]["id"], 1);
+        assert_eq!(responses[1]["result"], json!({}));
+    }
+
+    #[test]
+    fn test_new_markets_since_validates_bounds() {
+        let now = chrono::Utc::now();
+
+        let since = new_markets_since(&json!({"since": "2024-06-01T00:00:00Z"}), now).unwrap();
+        assert_eq!(since.to_rfc3339(), "2024-06-01T00:00:00+00:00");
+        let since = new_markets_since(&json!({"hours_ago": 6}), now).unwrap();
+        assert_eq!(now - since, chrono::Duration::hours(6));
+        let since = new_markets_since(&json!({}), now).unwrap();
+        assert_eq!(now - since, chrono::Duration::hours(24));
+
+        let future = (now + chrono::Duration::hours(1)).to_rfc3339();
+        for args in [
+            json!({"since": future}),
+            json!({"since": "yesterday"}),
+            json!({"hours_ago": 0}),
+            json!({"hours_ago": u32::MAX}),
+            json!({"since": "2024-06-01T00:00:00Z", "hours_ago": 6}),
+        ] {
+            let error = new_markets_since(&args, now).unwrap_err();
+            assert_eq!(error.code, RpcError::INVALID_PARAMS, "{args}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_batch_returns_responses_in_order() {
+        let server = test_server();
+        let session = test_session();
+        let batch = json!([
+            {"jsonrpc": "2.0", "id": "a", "method": "tools/list"},
+            {"jsonrpc": "2.0", "method": "notifications/initialized"},
+            {"jsonrpc": "2.0", "id": "b", "method": "resources/list"}
+        ]);
+
+        let response = handle_line(&server, &session, &batch.to_string())
+            .await
+            .unwrap();
+        let responses = response.as_array().unwrap();
+        assert_eq!(responses.len(), 2);
+        assert_eq!(responses[0]["id"], "a");
+        assert!(responses[0]["result"]["tools"].is_array());
+        assert_eq!(responses[1]["id"], "b");
+        assert!(responses[1]["result"]["resources"].is_array());
+
+        let response = handle_line(&server, &session, "[]").await.unwrap();
+        assert_eq!(response["error"]["code"], RpcError::INVALID_REQUEST);
+        assert!(response["id"].is_null());
+
+        let notifications = json!([{"jsonrpc": "2.0", "method": "notifications/initialized"}]);
+        assert!(handle_line(&server, &session, &notifications.to_string())
+            .await
+            .is_none());
+    }
+
+    #[test]
+    fn test_completion_result_filters_and_caps() {
+        let result = completion_result(
+            vec!["Politics".into(), "Crypto".into(), "Pop Culture".into()],
+            "po",
+        );
+        assert_eq!(
+            result["completion"]["values"],
+            json!(["Politics", "Pop Culture"])
+        );
+        assert_eq!(result["completion"]["total"], 2);
+        assert_eq!(result["completion"]["hasMore"], false);
+
+        let ids: Vec<String> = (0..150).map(|i| i.to_string()).collect();
+        let result = completion_result(ids, "");
+        assert_eq!(
+            result["completion"]["values"].as_array().unwrap().len(),
+            MAX_COMPLETIONS
+        );
+        assert_eq!(result["completion"]["total"], 150);
+        assert_eq!(result["completion"]["hasMore"], true);
+    }
+
+    #[tokio::test]
+    async fn test_completions_can_be_disabled() {
+        let mut config = Config::default();
+        config.server.completions = false;
+        let server = Arc::new(PolymarketMcpServer::with_config(config).unwrap());
+
+        let response = handle_mcp_request(
+            &server,
+            &test_session(),
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "initialize",
+                "params": {"protocolVersion": "2025-03-26", "capabilities": {}}
+            }),
+        )
+        .await
+        .unwrap();
+        assert!(response["result"]["capabilities"]
+            .get("completions")
+            .is_none());
+
+        let response = handle_mcp_request(
+            &server,
+            &test_session(),
+            json!({
+                "jsonrpc": "2.0",
+                "id": 2,
+                "method": "completion/complete",
+                "params": {
+                    "ref": {"type": "ref/prompt", "name": "market_summary"},
+                    "argument": {"name": "category", "value": ""}
+                }
+            }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response["error"]["code"], RpcError::METHOD_NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_completion_for_unknown_argument_is_empty() {
+        let server = test_server();
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "completion/complete",
+            "params": {
+                "ref": {"type": "ref/prompt", "name": "portfolio_summary"},
+                "argument": {"name": "user_address", "value": "0x"}
+            }
+        });
+        let response = handle_mcp_request(&server, &test_session(), request)
+            .await
+            .unwrap();
+        assert_eq!(response["result"]["completion"]["values"], json!([]));
+        assert_eq!(response["result"]["completion"]["hasMore"], false);
+    }
+
+    #[tokio::test]
+    async fn test_ping_returns_empty_result() {
+        let server = test_server();
+        let request = json!({"jsonrpc": "2.0", "id": 1, "method": "ping"});
+        let response = handle_mcp_request(&server, &test_session(), request)
+            .await
+            .unwrap();
+
+        assert_eq!(response["id"], 1);
+        assert_eq!(response["result"], json!({}));
+        assert!(response.get("error").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_unknown_method_returns_error_object() {
+        let server = test_server();
+        let request = json!({"jsonrpc": "2.0", "id": 7, "method": "does/not/exist"});
+        let response = handle_mcp_request(&server, &test_session(), request)
+            .await
+            .unwrap();
+
+        assert_eq!(response["id"], 7);
+        assert_eq!(response["error"]["code"], RpcError::METHOD_NOT_FOUND);
+        assert!(response.get("result").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_initialize_negotiates_protocol_version() {
+        let server = test_server();
+        let initialize = |version: &str| {
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "initialize",
+                "params": {"protocolVersion": version, "capabilities": {}}
+            })
+        };
+
+        let response = handle_mcp_request(&server, &test_session(), initialize("2025-03-26"))
+            .await
+            .unwrap();
+        assert_eq!(response["result"]["protocolVersion"], "2025-03-26");
+        let capabilities = &response["result"]["capabilities"];
+        assert_eq!(capabilities["tools"]["listChanged"], false);
+        assert_eq!(capabilities["resources"]["subscribe"], true);
+        assert_eq!(capabilities["completions"], json!({}));
+
+        let response = handle_mcp_request(&server, &test_session(), initialize("2024-11-05"))
+            .await
+            .unwrap();
+        assert_eq!(response["result"]["protocolVersion"], "2024-11-05");
+        assert_eq!(response["result"]["capabilities"]["completions"], json!({}));
+
+        let response = handle_mcp_request(&server, &test_session(), initialize("1999-01-01"))
+            .await
+            .unwrap();
+        assert_eq!(
+            response["result"]["protocolVersion"],
+            SUPPORTED_PROTOCOL_VERSIONS[0]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_closing_soon_rejects_oversized_window() {
+        let server = test_server();
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "tools/call",
+            "params": {
+                "name": "get_markets_closing_soon",
+                "arguments": {"within_hours": u32::MAX}
+            }
+        });
+        let response = handle_mcp_request(&server, &test_session(), request)
+            .await
+            .unwrap();
+
+        assert_eq!(response["error"]["code"], RpcError::INVALID_PARAMS);
+        assert_eq!(response["error"]["data"]["field"], "within_hours");
+    }
+
+    #[tokio::test]
+    async fn test_missing_tool_argument_returns_invalid_params() {
+        let server = test_server();
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "tools/call",
+            "params": {"name": "get_market_details", "arguments": {}}
+        });
+        let response = handle_mcp_request(&server, &test_session(), request)
+            .await
+            .unwrap();
+
+        assert_eq!(response["error"]["code"], RpcError::INVALID_PARAMS);
+        assert_eq!(
+            response["error"]["message"],
+            "market_id argument is required"
+        );
+        assert_eq!(
+            response["error"]["data"],
+            json!({"field": "market_id", "reason": "argument is required"})
+        );
+
+        // Prompts report missing arguments the same way
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "prompts/get",
+            "params": {"name": "portfolio_summary", "arguments": {}}
+        });
+        let response = handle_mcp_request(&server, &test_session(), request)
+            .await
+            .unwrap();
+        assert_eq!(response["error"]["code"], RpcError::INVALID_PARAMS);
+        assert_eq!(response["error"]["data"]["field"], "user_address");
+
+        // ...and malformed ones rejected by the client before any request is made
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 3,
+            "method": "prompts/get",
+            "params": {"name": "portfolio_summary", "arguments": {"user_address": "0x123"}}
+        });
+        let response = handle_mcp_request(&server, &test_session(), request)
+            .await
+            .unwrap();
+        assert_eq!(response["error"]["code"], RpcError::INVALID_PARAMS);
+        assert_eq!(response["error"]["data"]["field"], "user_address");
+    }
+
+    #[tokio::test]
+    async fn test_tool_results_carry_request_id() {
+        let server = test_server();
+        let params = json!({"name": "get_metrics", "arguments": {}});
+        let first = call_tool(&server, &params).await.unwrap();
+        let second = call_tool(&server, &params).await.unwrap();
+        assert!(first["request_id"].is_string());
+        assert_ne!(first["request_id"], second["request_id"]);
+
+        // A failed tool call is tagged too
+        let params = json!({"name": "get_market_details", "arguments": {"market_id": "1"}});
+        let failed = call_tool(&server, &params).await.unwrap();
+        assert_eq!(failed["isError"], true);
+        assert!(failed["request_id"].is_string());
+        let payload: Value =
+            serde_json::from_str(failed["content"][1]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(payload["error"]["request_id"], failed["request_id"]);
+
+        let api_error = PolymarketError::api_error("HTTP error: boom", Some(500));
+        let PolymarketError::Api { request_id, .. } = &api_error else {
+            unreachable!()
+        };
+        let response = tool_response(Err(api_error.clone()), &RequestId::new());
+        assert_eq!(response["request_id"], json!(request_id));
+    }
+
+    #[test]
+    fn test_tool_errors_carry_structured_payload() {
+        let response = tool_response(
+            Err(PolymarketError::api_error("HTTP error: boom", Some(503))),
+            &RequestId::new(),
+        );
+        assert!(response["content"][0]["text"]
+            .as_str()
+            .unwrap()
+            .starts_with("Error: API request failed"));
+        let payload: Value =
+            serde_json::from_str(response["content"][1]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(payload["error"]["error_type"], "api_error");
+        assert_eq!(payload["error"]["status_code"], 503);
+        assert_eq!(payload["error"]["request_id"], response["request_id"]);
+
+        let request_id = RequestId("req-7".to_string());
+        let payload = tool_error_payload(&PolymarketError::not_found("Market 7"), &request_id);
+        assert_eq!(
+            payload,
+            json!({"error": {
+                "error_type": "not_found",
+                "message": "Market 7 not found",
+                "status_code": 404,
+                "request_id": "req-7",
+                "attempts": null,
+                "total_elapsed_ms": null
+            }})
+        );
+
+        let payload = tool_error_payload(
+            &PolymarketError::internal_error("Reload handle unavailable"),
+            &request_id,
+        );
+        assert_eq!(payload["error"]["error_type"], "internal_error");
+        assert_eq!(payload["error"]["status_code"], Value::Null);
+    }
+
+    #[test]
+    fn test_ndjson_format_emits_one_market_per_line() {
+        let result = json!({
+            "markets": [{"id": "1"}, {"id": "2"}],
+            "count": 2
+        });
+
+        let response = OutputFormat::Ndjson.markets_response(Ok(result.clone()), &RequestId::new());
+        assert_eq!(
+            response["content"][1]["text"],
+            "{\"id\":\"1\"}\n{\"id\":\"2\"}"
+        );
+
+        let response = OutputFormat::Json.markets_response(Ok(result.clone()), &RequestId::new());
+        let body: Value =
+            serde_json::from_str(response["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(body, result);
+
+        assert!(output_format(&json!({"format": "xml"})).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_tool_calls_are_counted() {
+        let server = test_server();
+        let call = |name: &str| {
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "tools/call",
+                "params": {"name": name, "arguments": {}}
+            })
+        };
+
+        for name in ["get_metrics", "get_metrics", "no_such_tool"] {
+            handle_mcp_request(&server, &test_session(), call(name)).await;
+        }
+
+        let metrics = server.get_metrics().await.unwrap();
+        assert_eq!(metrics["tool_calls"], json!({"get_metrics": 2}));
+    }
+
+    #[test]
+    fn test_tool_names_match_definitions() {
+        let names: Vec<Value> = tool_definitions()
+            .into_iter()
+            .map(|tool| tool["name"].clone())
+            .collect();
+        assert_eq!(names, TOOL_NAMES);
+    }
+
+    #[tokio::test]
+    async fn test_set_log_level_applies_filter_and_returns_previous() {
+        let mut config = Config::default();
+        config.api.base_url = "http://127.0.0.1:9".to_string();
+        config.cache.enabled = false;
+        let applied = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorder = applied.clone();
+        let server = Arc::new(
+            PolymarketMcpServer::with_config(config)
+                .unwrap()
+                .with_log_filter(move |level| {
+                    recorder.lock().unwrap().push(level.to_string());
+                    Ok(())
+                }),
+        );
+
+        let params = json!({"name": "set_log_level", "arguments": {"level": "DEBUG"}});
+        let result = call_tool(&server, &params).await.unwrap();
+        let body: Value =
+            serde_json::from_str(result["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(body, json!({"previous_level": "info", "level": "debug"}));
+        assert_eq!(server.config().logging.level, "debug");
+        assert_eq!(*applied.lock().unwrap(), vec!["debug"]);
+
+        let params = json!({"name": "set_log_level", "arguments": {"level": "loud"}});
+        let error = call_tool(&server, &params).await.unwrap_err();
+        assert!(error.message.contains("Invalid level 'loud'"));
+
+        // Without a reload handle the change is reported as a tool error
+        let params = json!({"name": "set_log_level", "arguments": {"level": "warn"}});
+        let result = call_tool(&test_server(), &params).await.unwrap();
+        assert_eq!(result["isError"], true);
+    }
+
+    #[tokio::test]
+    async fn test_get_metrics_reports_cache_sizes() {
+        let server = test_server();
+        server.resource_cache.write().await.insert(
+            "markets:active".to_string(),
+            ResourceCache::new("[]".to_string(), 60),
+        );
+
+        let metrics = server.get_metrics().await.unwrap();
+        assert_eq!(
+            metrics["cache_sizes"],
+            json!({"market": 0, "single_market": 0, "resource": 1})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_invalidate_cache_by_uri_and_all() {
+        let server = test_server();
+        {
+            let mut cache = server.resource_cache.write().await;
+            for uri in ["market:1", "markets:active"] {
+                cache.insert(uri.to_string(), ResourceCache::new("{}".to_string(), 60));
+            }
+        }
+
+        let result = server
+            .invalidate_cache(None, Some("1".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(result["evicted"], 1);
+
+        let result = server.invalidate_cache(None, None).await.unwrap();
+        assert_eq!(result["evicted"], 1);
+        assert!(server.resource_cache.read().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_empty_watchlist_resource() {
+        let server = test_server();
+        let resources = server.list_resources().await.unwrap();
+        assert!(resources["resources"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|resource| resource["uri"] == "markets:watchlist"));
+
+        let result = server.read_resource("markets:watchlist").await.unwrap();
+        let content: Value =
+            serde_json::from_str(result["contents"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(content["markets"], json!([]));
+        assert_eq!(content["count"], 0);
+    }
+
+    #[tokio::test]
+    async fn test_resource_subscription_notifications() {
+        let server = test_server();
+        let (notifier, mut notifications) = mpsc::channel(NOTIFICATION_BUFFER);
+        let session = Session::new(notifier);
+        let request = |method: &str, uri: &str| json!({"jsonrpc": "2.0", "id": 1, "method": method, "params": {"uri": uri}});
+
+        let response = handle_mcp_request(
+            &server,
+            &session,
+            request("resources/subscribe", "market:1"),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response["error"]["code"], RpcError::INVALID_PARAMS);
+
+        let response = handle_mcp_request(
+            &server,
+            &session,
+            request("resources/subscribe", "markets:active"),
+        )
+        .await
+        .unwrap();
+        assert!(response["result"].is_object());
+
+        server.notify_resource_updated("markets:active").await;
+        let notification = notifications.try_recv().unwrap();
+        assert_eq!(notification["method"], "notifications/resources/updated");
+        assert_eq!(notification["params"]["uri"], "markets:active");
+
+        handle_mcp_request(
+            &server,
+            &session,
+            request("resources/unsubscribe", "markets:active"),
+        )
+        .await
+        .unwrap();
+        server.notify_resource_updated("markets:active").await;
+        assert!(notifications.try_recv().is_err());
+        assert!(server.subscriptions.read().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_subscribed_resources_notify_only_on_change() {
+        let mut api = mockito::Server::new_async().await;
+        let market = |price: &str| {
+            json!([{
+                "id": "1",
+                "slug": "market-1",
+                "question": "Will market 1 resolve yes?",
+                "active": true,
+                "closed": false,
+                "liquidity": "1000",
+                "volume": "5000",
+                "endDate": "2030-01-01T00:00:00Z",
+                "outcomes": "[\"Yes\", \"No\"]",
+                "outcomePrices": format!("[\"{price}\", \"0.4\"]")
+            }])
+            .to_string()
+        };
+        let mock = api
+            .mock("GET", "/markets")
+            .match_query(mockito::Matcher::Any)
+            .with_body(market("0.6"))
+            .create_async()
+            .await;
+        let mut config = Config::default();
+        config.api.base_url = api.url();
+        config.api.max_retries = 1;
+        config.cache.enabled = false;
+        let server = PolymarketMcpServer::with_config(config).unwrap();
+        let (notifier, mut notifications) = mpsc::channel(NOTIFICATION_BUFFER);
+        let session = Session::new(notifier);
+        server.subscribe(&session, "markets:active").await;
+        let mut refreshed_at = HashMap::new();
+
+        assert!(server
+            .refresh_subscriptions(&mut refreshed_at)
+            .await
+            .is_empty());
+        server.read_resource("markets:active").await.unwrap();
+        refreshed_at.clear();
+        assert!(server
+            .refresh_subscriptions(&mut refreshed_at)
+            .await
+            .is_empty());
+        assert!(notifications.try_recv().is_err());
+
+        mock.remove_async().await;
+        api.mock("GET", "/markets")
+            .match_query(mockito::Matcher::Any)
+            .with_body(market("0.7"))
+            .create_async()
+            .await;
+        assert!(server
+            .refresh_subscriptions(&mut refreshed_at)
+            .await
+            .is_empty());
+        refreshed_at.clear();
+        assert_eq!(
+            server.refresh_subscriptions(&mut refreshed_at).await,
+            ["markets:active"]
+        );
+        let notification = notifications.try_recv().unwrap();
+        assert_eq!(notification["params"]["uri"], "markets:active");
+    }
+
+    #[tokio::test]
+    async fn test_event_markets_tool_fetches_event_once() {
+        let mut api = mockito::Server::new_async().await;
+        let event = api
+            .mock("GET", "/events/e1")
+            .with_body(
+                json!({
+                    "id": "e1",
+                    "title": "Who wins the primary?",
+                    "markets": [{
+                        "id": "1",
+                        "slug": "market-1",
+                        "question": "Will candidate A win?",
+                        "active": true,
+                        "closed": false,
+                        "liquidity": "1000",
+                        "volume": "5000",
+                        "endDate": "2030-01-01T00:00:00Z",
+                        "outcomes": "[\"Yes\", \"No\"]",
+                        "outcomePrices": "[\"0.6\", \"0.4\"]"
+                    }]
+                })
+                .to_string(),
+            )
+            .expect(1)
+            .create_async()
+            .await;
+        let mut config = Config::default();
+        config.api.base_url = api.url();
+        config.cache.enabled = false;
+        let server = PolymarketMcpServer::with_config(config).unwrap();
+
+        let result = server.get_event_markets("e1".to_string()).await.unwrap();
+        assert_eq!(result["title"], "Who wins the primary?");
+        assert_eq!(result["count"], 1);
+        assert_eq!(result["markets"][0]["id"], "1");
+        event.assert_async().await;
+        assert_eq!(server.client.get_metrics().await.coalesced_requests, 0);
+    }
+
+    #[tokio::test]
+    async fn test_network_failure_maps_to_network_error_code() {
+        let server = test_server();
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "resources/read",
+            "params": {"uri": "market:123"}
+        });
+        let response = handle_mcp_request(&server, &test_session(), request)
+            .await
+            .unwrap();
+
+        assert_eq!(response["error"]["code"], RpcError::NETWORK_ERROR);
+    }
+
+    #[tokio::test]
+    async fn test_prompt_templates_from_prompts_dir() {
+        let dir = std::env::temp_dir().join(format!("prompts-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir(&dir).unwrap();
+        std::fs::write(
+            dir.join("analyze_market.json"),
+            json!({
+                "name": "analyze_market",
+                "description": "Custom analysis",
+                "arguments": [{"name": "market_id", "description": "Market", "required": true}],
+                "template": "Take a close look at market {market_id}."
+            })
+            .to_string(),
+        )
+        .unwrap();
+
+        let mut config = Config::default();
+        config.api.base_url = "http://127.0.0.1:9".to_string();
+        config.server.prompts_dir = Some(dir.to_string_lossy().into_owned());
+        let server = PolymarketMcpServer::with_config(config).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let prompts = server.list_prompts().await.unwrap();
+        let analyze: Vec<&Value> = prompts["prompts"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|prompt| prompt["name"] == "analyze_market")
+            .collect();
+        assert_eq!(analyze.len(), 1);
+        assert_eq!(analyze[0]["description"], "Custom analysis");
+
+        let prompt = server
+            .get_prompt("analyze_market", Some(json!({"market_id": "42"})))
+            .await
+            .unwrap();
+        assert_eq!(
+            prompt["messages"][0]["content"],
+            "Take a close look at market 42."
+        );
+    }
+
+    #[tokio::test]
+    async fn test_find_arbitrage_prompt_searches_once() {
+        let market = |id: &str| {
+            json!({
+                "id": id,
+                "slug": format!("bitcoin-{id}"),
+                "question": format!("Will Bitcoin reach 100k by 203{id}?"),
+                "active": true,
+                "closed": false,
+                "liquidity": "1000",
+                "volume": "5000",
+                "endDate": "2030-01-01T00:00:00Z",
+                "outcomes": "[\"Yes\", \"No\"]",
+                "outcomePrices": "[\"0.6\", \"0.4\"]"
+            })
+        };
+        let mut api = mockito::Server::new_async().await;
+        let search = api
+            .mock("GET", "/markets")
+            .match_query(mockito::Matcher::Any)
+            .with_body(json!([market("1"), market("2")]).to_string())
+            .expect(1)
+            .create_async()
+            .await;
+        let mut config = Config::default();
+        config.api.base_url = api.url();
+        config.api.max_limit = 1;
+        let server = PolymarketMcpServer::with_config(config).unwrap();
+
+        let prompt = server
+            .get_prompt(
+                "find_arbitrage",
+                Some(json!({"keyword": "bitcoin", "limit": 50})),
+            )
+            .await
+            .unwrap();
+        search.assert_async().await;
+        let text = prompt["messages"][0]["content"].as_str().unwrap();
+        // The limit is capped at api.max_limit like the tool's
+        assert!(text.contains("Markets found: 1\n"), "{text}");
+    }
+
+    #[tokio::test]
+    async fn test_unknown_prompt_and_resource_are_invalid_params() {
+        let server = test_server();
+        for (method, params) in [
+            ("prompts/get", json!({"name": "nonexistent"})),
+            ("resources/read", json!({"uri": "markets:nonexistent"})),
+        ] {
+            let request = json!({"jsonrpc": "2.0", "id": 3, "method": method, "params": params});
+            let response = handle_mcp_request(&server, &test_session(), request)
+                .await
+                .unwrap();
+            assert_eq!(response["error"]["code"], RpcError::INVALID_PARAMS);
+            assert!(response["error"]["message"]
+                .as_str()
+                .unwrap()
+                .starts_with("Resource error: Unknown"));
+        }
+    }
+
+    #[test]
+    fn test_not_found_maps_to_not_found_code() {
+        let error = RpcError::from(PolymarketError::not_found("Market 123"));
+        assert_eq!(error.code, RpcError::NOT_FOUND);
+        assert_eq!(error.message, "Market 123 not found");
+        assert_eq!(error.data, Some(json!({"resource": "Market 123"})));
+    }
+
+    #[test]
+    fn test_limit_arguments_are_clamped() {
+        let server = test_server();
+        let max = server.config().api.max_limit;
+
+        assert_eq!(server.effective_limit(None, 20), 20);
+        assert_eq!(server.effective_limit(Some(0), 20), 20);
+        assert_eq!(server.effective_limit(Some(5), 20), 5);
+        assert_eq!(server.effective_limit(Some(max + 1), 20), max);
+
+        let args = json!({ "negative": -3, "huge": 1u64 << 40, "fraction": 2.5 });
+        assert_eq!(optional_u32(&args, "negative"), None);
+        assert_eq!(optional_u32(&args, "huge"), Some(u32::MAX));
+        assert_eq!(optional_u32(&args, "fraction"), None);
+    }
+
+    #[test]
+    fn test_keyword_argument_accepts_string_or_array() {
+        let args = json!({
+            "single": "bitcoin",
+            "several": ["election", "2024"],
+            "empty": [],
+            "mixed": ["bitcoin", 1]
+        });
+        assert_eq!(required_keywords(&args, "single").unwrap(), vec!["bitcoin"]);
+        assert_eq!(
+            required_keywords(&args, "several").unwrap(),
+            vec!["election", "2024"]
+        );
+        assert!(required_keywords(&args, "empty").is_err());
+        assert!(required_keywords(&args, "mixed").is_err());
+        assert!(required_keywords(&args, "missing").is_err());
+    }
+
+    #[test]
+    fn test_portfolio_summary_text() {
+        let position = |market_id: &str, value, pnl| Position {
+            id: format!("tok-{market_id}"),
+            market_id: market_id.to_string(),
+            user_address: "0xabc".to_string(),
+            outcome_id: "Yes".to_string(),
+            shares: 100.0,
+            value,
+            cost_basis: value - pnl,
+            unrealized_pnl: pnl,
+        };
+        let positions = vec![position("0xa", 75.0, 25.0), position("0xb", 25.0, -10.0)];
+
+        let text = portfolio_summary_text("0xabc", &positions, &[]);
+
+        assert!(text.contains("Total value: $100.00"));
+        assert!(text.contains("Unrealized PnL: $15.00"));
+        assert!(text.contains("Largest position: 0xa (Yes) at 75.0% of portfolio value"));
+    }
+
+    #[test]
+    fn test_market_activity_text_notes_missing_data() {
+        let books = vec![
+            TopOfBook {
+                outcome: "Yes".to_string(),
+                best_bid: Some(0.52),
+                best_ask: Some(0.55),
+                mid: Some(0.535),
+            },
+            TopOfBook::empty("No"),
+        ];
+        let trade = Trade {
+            id: "0x1".to_string(),
+            market_id: "0xc".to_string(),
+            outcome_id: "Yes".to_string(),
+            side: "buy".to_string(),
+            size: 100.0,
+            price: 0.53,
+            timestamp: "2024-06-01T00:00:00Z".to_string(),
+            trader_address: None,
+        };
+
+        let text = market_activity_text(&books, Some(&[trade]));
+        assert!(text.contains("- Yes: bid 0.520 / ask 0.550 (spread 0.030)"));
+        assert!(!text.contains("- No:"));
+        assert!(text.contains("- 2024-06-01T00:00:00Z: BUY 100.00 Yes @ 0.530"));
+
+        let text = market_activity_text(&[TopOfBook::empty("Yes")], None);
+        assert!(text.contains("Order book data unavailable"));
+        assert!(text.contains("Recent trades unavailable"));
+    }
+
+    #[test]
+    fn test_round_price_fields() {
+        let mut value = json!({
+            "total_implied_probability": 1.0000000000000002,
+            "prices": [{"price": 0.6000000000000001, "outcome_index": 0}],
+            "order_book": {"bids": [{"price": 0.123456, "size": 10.123456}]},
+            "volume": 1234.56789
+        });
+        round_price_fields(&mut value, 4);
+
+        assert_eq!(value["total_implied_probability"], 1.0);
+        assert_eq!(value["prices"][0]["price"], 0.6);
+        assert_eq!(value["order_book"]["bids"][0]["price"], 0.1235);
+        assert_eq!(value["order_book"]["bids"][0]["size"], 10.123456);
+        assert_eq!(value["volume"], 1234.56789);
+    }
+
+    #[test]
+    fn test_price_response_reports_overround() {
+        let price = |outcome: &str, index, price| MarketPrice {
+            market_id: "1".to_string(),
+            outcome_id: outcome.to_string(),
+            outcome_index: index,
+            price,
+            timestamp: String::new(),
+        };
+        let response = price_response("1", &[price("Yes", 0, 0.53), price("No", 1, 0.5)]);
+
+        let total = response["total_implied_probability"].as_f64().unwrap();
+        assert!((total - 1.03).abs() < 1e-9);
+        assert!((response["overround"].as_f64().unwrap() - 0.03).abs() < 1e-9);
+        let yes = response["prices"][0]["implied_probability"]
+            .as_f64()
+            .unwrap();
+        assert!((yes - 0.53 / 1.03).abs() < 1e-9);
+        assert_eq!(response["prices"][1]["outcome_id"], "No");
+    }
 }
@@ -0,0 +1,114 @@
+//! Parsing of polymarket.com links into the slugs they reference.
+
+use crate::polymarket_client::is_valid_slug;
+use serde::Serialize;
+
+/// Hosts serving the Polymarket web app
+const POLYMARKET_HOSTS: &[&str] = &["polymarket.com", "www.polymarket.com"];
+
+/// What a polymarket.com link points at.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum MarketUrl {
+    /// `/event/<event_slug>`, optionally narrowed to one market with
+    /// `/event/<event_slug>/<market_slug>`
+    Event {
+        event_slug: String,
+        market_slug: Option<String>,
+    },
+    /// `/market/<slug>`
+    Market { slug: String },
+}
+
+/// Parses a polymarket.com event or market link.
+///
+/// The scheme may be omitted, and query strings, fragments and trailing slashes are
+/// ignored.
+///
+/// # Errors
+///
+/// Returns a message describing the expected forms when `url` is not a Polymarket
+/// link or its path matches neither `/event/...` nor `/market/...`.
+pub fn parse(url: &str) -> Result<MarketUrl, String> {
+    let url = url.trim();
+    let with_scheme = if url.contains("://") {
+        url.to_string()
+    } else {
+        format!("https://{url}")
+    };
+    let invalid = || {
+        format!(
+            "Unrecognized Polymarket URL '{url}': expected https://polymarket.com/event/<slug>[/<market-slug>] or https://polymarket.com/market/<slug>"
+        )
+    };
+
+    let parsed = reqwest::Url::parse(&with_scheme).map_err(|_| invalid())?;
+    let is_polymarket = parsed
+        .host_str()
+        .is_some_and(|host| POLYMARKET_HOSTS.contains(&host.to_ascii_lowercase().as_str()));
+    if !is_polymarket {
+        return Err(invalid());
+    }
+
+    let segments: Vec<&str> = parsed
+        .path_segments()
+        .map(|segments| segments.filter(|s| !s.is_empty()).collect())
+        .unwrap_or_default();
+    if !segments.iter().skip(1).all(|slug| is_valid_slug(slug)) {
+        return Err(invalid());
+    }
+
+    match segments.as_slice() {
+        ["event", event_slug] => Ok(MarketUrl::Event {
+            event_slug: event_slug.to_string(),
+            market_slug: None,
+        }),
+        ["event", event_slug, market_slug] => Ok(MarketUrl::Event {
+            event_slug: event_slug.to_string(),
+            market_slug: Some(market_slug.to_string()),
+        }),
+        ["market", slug] => Ok(MarketUrl::Market {
+            slug: slug.to_string(),
+        }),
+        _ => Err(invalid()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_event_and_market_urls() {
+        assert_eq!(
+            parse("https://polymarket.com/event/presidential-election-winner-2024/").unwrap(),
+            MarketUrl::Event {
+                event_slug: "presidential-election-winner-2024".to_string(),
+                market_slug: None,
+            }
+        );
+        assert_eq!(
+            parse("polymarket.com/event/foo/bar?tid=123#comments").unwrap(),
+            MarketUrl::Event {
+                event_slug: "foo".to_string(),
+                market_slug: Some("bar".to_string()),
+            }
+        );
+        assert_eq!(
+            parse("https://www.polymarket.com/market/will-it-rain").unwrap(),
+            MarketUrl::Market {
+                slug: "will-it-rain".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_other_urls() {
+        assert!(parse("https://example.com/event/foo").is_err());
+        assert!(parse("https://polymarket.com/").is_err());
+        assert!(parse("https://polymarket.com/profile/0xabc").is_err());
+        assert!(parse("https://polymarket.com/event/foo/bar/baz").is_err());
+        assert!(parse("https://polymarket.com/event/Bad_Slug").is_err());
+        assert!(parse("not a url").is_err());
+    }
+}
@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Deserializer, Serialize};
 use std::time::{SystemTime, UNIX_EPOCH};
 
@@ -65,7 +66,11 @@ pub struct Market {
     pub accepting_orders: Option<bool>,
     #[serde(rename = "acceptingOrderTimestamp", default)]
     pub accepting_order_timestamp: Option<String>,
-    #[serde(rename = "clobTokenIds", default)]
+    #[serde(
+        rename = "clobTokenIds",
+        deserialize_with = "deserialize_optional_json_string_to_vec",
+        default
+    )]
     pub clob_token_ids: Option<Vec<String>>,
     #[serde(rename = "fpmm", default)]
     pub fpmm: Option<String>,
@@ -106,11 +111,164 @@ impl Market {
         }
     }
 
-    /// Check if market expires soon (within 24 hours) - requires parsing end_date
+    /// The winning outcome of a closed market, if its prices have settled.
+    ///
+    /// A market counts as resolved when exactly one outcome is priced at 0.99 or
+    /// above and every other outcome at 0.01 or below. Open markets and ambiguous
+    /// prices yield `None`.
+    #[must_use]
+    pub fn resolved_outcome(&self) -> Option<String> {
+        if !self.closed || self.outcomes.len() != self.outcome_prices.len() {
+            return None;
+        }
+        let prices: Vec<f64> = self
+            .outcome_prices
+            .iter()
+            .map(|price| price.parse().ok())
+            .collect::<Option<_>>()?;
+
+        let mut winner = None;
+        for (outcome, price) in self.outcomes.iter().zip(prices) {
+            if price >= 0.99 {
+                if winner.is_some() {
+                    return None;
+                }
+                winner = Some(outcome);
+            } else if price > 0.01 {
+                return None;
+            }
+        }
+        winner.cloned()
+    }
+
+    /// Parses `end_date` as RFC3339, returning `None` if it is missing or malformed.
+    #[must_use]
+    pub fn parsed_end_date(&self) -> Option<DateTime<Utc>> {
+        DateTime::parse_from_rfc3339(&self.end_date)
+            .ok()
+            .map(|end| end.with_timezone(&Utc))
+    }
+
+    /// Parses `start_date` as RFC3339, returning `None` if it is missing or malformed.
+    #[must_use]
+    pub fn parsed_start_date(&self) -> Option<DateTime<Utc>> {
+        let start = self.start_date.as_deref()?;
+        DateTime::parse_from_rfc3339(start)
+            .ok()
+            .map(|start| start.with_timezone(&Utc))
+    }
+
+    /// The outcome with the highest quoted price and that price, skipping unparseable prices.
+    #[must_use]
+    pub fn favorite_outcome(&self) -> Option<(&str, f64)> {
+        self.outcomes
+            .iter()
+            .zip(&self.outcome_prices)
+            .filter_map(|(outcome, price)| Some((outcome.as_str(), price.parse::<f64>().ok()?)))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+    }
+
+    /// Hours from `now` until `end_date`; negative once the end date has passed.
+    #[must_use]
+    pub fn time_to_resolution_hours(&self, now: DateTime<Utc>) -> Option<f64> {
+        let remaining = self.parsed_end_date()? - now;
+        Some(remaining.num_seconds() as f64 / 3600.0)
+    }
+
+    /// Pairs each outcome label with its quoted price.
+    ///
+    /// `implied_probability` normalizes the prices to sum to 1, removing the book's
+    /// overround. If the two arrays differ in length a warning is logged and the
+    /// extra entries of the longer one are dropped; an unparseable price yields
+    /// `None` for that outcome.
+    #[must_use]
+    pub fn structured_outcomes(&self) -> Vec<MarketOutcome> {
+        if self.outcomes.len() != self.outcome_prices.len() {
+            tracing::warn!(
+                "Market {} has {} outcomes but {} prices; ignoring the unmatched entries",
+                self.id,
+                self.outcomes.len(),
+                self.outcome_prices.len()
+            );
+        }
+
+        let prices: Vec<Option<f64>> = self
+            .outcome_prices
+            .iter()
+            .map(|price| price.parse().ok())
+            .collect();
+        let total: f64 = prices.iter().flatten().sum();
+
+        self.outcomes
+            .iter()
+            .zip(prices)
+            .enumerate()
+            .map(|(index, (name, price))| MarketOutcome {
+                index,
+                name: name.clone(),
+                price,
+                implied_probability: price.filter(|_| total > 0.0).map(|p| p / total),
+            })
+            .collect()
+    }
+
+    /// Check if market expires soon (within 24 hours)
     #[allow(dead_code)]
     pub fn expires_soon(&self) -> bool {
-        // This would need proper date parsing implementation
-        false // Placeholder
+        self.time_to_resolution_hours(Utc::now())
+            .is_some_and(|hours| (0.0..=24.0).contains(&hours))
+    }
+}
+
+/// One outcome of a market with its price, see [`Market::structured_outcomes`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarketOutcome {
+    /// Position of the outcome in `Market::outcomes`
+    pub index: usize,
+    pub name: String,
+    pub price: Option<f64>,
+    pub implied_probability: Option<f64>,
+}
+
+/// Orderings accepted by `get_active_markets`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MarketSort {
+    #[default]
+    Liquidity,
+    Volume,
+    /// Sorted locally, since the API does not reliably order by 24h volume
+    #[serde(rename = "volume_24hr")]
+    Volume24hr,
+    EndDate,
+}
+
+impl MarketSort {
+    /// The Gamma `order` parameter for this sort, or `None` if it is applied locally.
+    #[must_use]
+    pub fn api_order(self) -> Option<&'static str> {
+        match self {
+            Self::Liquidity => Some("liquidity"),
+            Self::Volume => Some("volume"),
+            Self::Volume24hr => None,
+            Self::EndDate => Some("endDate"),
+        }
+    }
+}
+
+impl std::str::FromStr for MarketSort {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "liquidity" => Ok(Self::Liquidity),
+            "volume" => Ok(Self::Volume),
+            "volume_24hr" => Ok(Self::Volume24hr),
+            "end_date" => Ok(Self::EndDate),
+            other => Err(format!(
+                "Invalid sort_by '{other}'. Valid options: liquidity, volume, volume_24hr, end_date"
+            )),
+        }
     }
 }
 
@@ -127,14 +285,21 @@ pub enum ActivityLevel {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Tag {
     pub id: String,
+    // The events endpoint labels tags with `label` rather than `name`
+    #[serde(alias = "label", default)]
     pub name: String,
+    // Some event tags carry only an id and label
+    #[serde(default)]
     pub slug: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MarketPrice {
     pub market_id: String,
+    /// Outcome label, e.g. "Yes" or "No"
     pub outcome_id: String,
+    /// Position of the outcome in `Market::outcomes`
+    pub outcome_index: usize,
     pub price: f64,
     pub timestamp: String,
 }
@@ -162,7 +327,10 @@ pub struct Event {
     #[serde(default)]
     pub slug: Option<String>,
     #[serde(default)]
-    pub tags: Option<Vec<String>>,
+    pub tags: Option<Vec<Tag>>,
+    /// Markets grouped under this event, when the endpoint embeds them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub markets: Option<Vec<Market>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -171,15 +339,54 @@ pub struct EventResponse {
     pub next_cursor: Option<String>,
 }
 
+/// Events payload as returned by the API: either a `{data, next_cursor}` envelope or a bare array
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum EventsPage {
+    Envelope(EventResponse),
+    List(Vec<Event>),
+}
+
+impl EventsPage {
+    /// Splits the page into its events and the cursor for the next page, if any
+    pub fn into_parts(self) -> (Vec<Event>, Option<String>) {
+        match self {
+            Self::Envelope(response) => (response.data, response.next_cursor),
+            Self::List(events) => (events, None),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Position {
+    // The data API identifies positions by outcome token and markets by condition id
+    #[serde(alias = "asset", default)]
     pub id: String,
+    #[serde(alias = "conditionId", default)]
     pub market_id: String,
+    #[serde(alias = "proxyWallet", default)]
     pub user_address: String,
+    #[serde(alias = "outcome", default)]
     pub outcome_id: String,
+    #[serde(
+        alias = "size",
+        deserialize_with = "deserialize_string_or_number_to_f64"
+    )]
     pub shares: f64,
+    #[serde(
+        alias = "currentValue",
+        deserialize_with = "deserialize_string_or_number_to_f64"
+    )]
     pub value: f64,
+    #[serde(
+        alias = "initialValue",
+        deserialize_with = "deserialize_string_or_number_to_f64"
+    )]
     pub cost_basis: f64,
+    #[serde(
+        alias = "cashPnl",
+        deserialize_with = "deserialize_string_or_number_to_f64"
+    )]
     pub unrealized_pnl: f64,
 }
 
@@ -189,15 +396,41 @@ pub struct PositionsResponse {
     pub next_cursor: Option<String>,
 }
 
+/// Positions payload as returned by the API: either a `{data, next_cursor}` envelope or a bare array
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum PositionsPage {
+    Envelope(PositionsResponse),
+    List(Vec<Position>),
+}
+
+impl PositionsPage {
+    /// Splits the page into its positions and the cursor for the next page, if any
+    pub fn into_parts(self) -> (Vec<Position>, Option<String>) {
+        match self {
+            Self::Envelope(response) => (response.data, response.next_cursor),
+            Self::List(positions) => (positions, None),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Trade {
+    // The data API identifies trades by transaction hash and markets by condition id
+    #[serde(alias = "transactionHash", default)]
     pub id: String,
+    #[serde(alias = "conditionId", default)]
     pub market_id: String,
+    #[serde(alias = "outcome", default)]
     pub outcome_id: String,
     pub side: String, // "buy" or "sell"
+    #[serde(deserialize_with = "deserialize_string_or_number_to_f64")]
     pub size: f64,
+    #[serde(deserialize_with = "deserialize_string_or_number_to_f64")]
     pub price: f64,
+    #[serde(deserialize_with = "deserialize_timestamp_to_rfc3339")]
     pub timestamp: String,
+    #[serde(alias = "proxyWallet", default)]
     pub trader_address: Option<String>,
 }
 
@@ -207,6 +440,44 @@ pub struct TradesResponse {
     pub next_cursor: Option<String>,
 }
 
+/// Trades payload as returned by the API: either a `{data, next_cursor}` envelope or a bare array
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum TradesPage {
+    Envelope(TradesResponse),
+    List(Vec<Trade>),
+}
+
+impl TradesPage {
+    /// Splits the page into its trades and the cursor for the next page, if any
+    pub fn into_parts(self) -> (Vec<Trade>, Option<String>) {
+        match self {
+            Self::Envelope(response) => (response.data, response.next_cursor),
+            Self::List(trades) => (trades, None),
+        }
+    }
+}
+
+/// One point of an outcome's price time series
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PricePoint {
+    /// Outcome label the point belongs to; filled in by the client
+    #[serde(default)]
+    pub outcome: String,
+    // The CLOB encodes points as `{t: unix seconds, p: price}`
+    #[serde(alias = "t", deserialize_with = "deserialize_timestamp_to_rfc3339")]
+    pub timestamp: String,
+    #[serde(alias = "p", deserialize_with = "deserialize_string_or_number_to_f64")]
+    pub price: f64,
+}
+
+/// CLOB `/prices-history` response for a single outcome token
+#[derive(Debug, Clone, Deserialize)]
+pub struct PriceHistoryResponse {
+    #[serde(default)]
+    pub history: Vec<PricePoint>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OrderBook {
     pub market_id: String,
@@ -216,12 +487,297 @@ pub struct OrderBook {
     pub timestamp: String,
 }
 
+impl OrderBook {
+    /// Highest bid price, if the book has any bids
+    #[must_use]
+    pub fn best_bid(&self) -> Option<f64> {
+        self.bids
+            .iter()
+            .map(|level| level.price)
+            .max_by(f64::total_cmp)
+    }
+
+    /// Lowest ask price, if the book has any asks
+    #[must_use]
+    pub fn best_ask(&self) -> Option<f64> {
+        self.asks
+            .iter()
+            .map(|level| level.price)
+            .min_by(f64::total_cmp)
+    }
+
+    /// Midpoint of the best bid and ask; `None` for a one-sided or empty book
+    #[must_use]
+    pub fn mid(&self) -> Option<f64> {
+        Some((self.best_bid()? + self.best_ask()?) / 2.0)
+    }
+
+    /// Best ask minus best bid; `None` for a one-sided or empty book
+    #[must_use]
+    pub fn spread(&self) -> Option<f64> {
+        Some(self.best_ask()? - self.best_bid()?)
+    }
+
+    /// Total size resting within `percent` of the mid price on each side.
+    ///
+    /// A one-sided book has no mid, so its depth is measured from the best price of
+    /// the side that exists. A side with no levels reports `None`.
+    #[must_use]
+    pub fn depth_within(&self, percent: f64) -> BookDepth {
+        let band = percent / 100.0;
+        let mid = self.mid();
+
+        let bids = mid.or(self.best_bid()).map(|reference| {
+            let floor = reference * (1.0 - band);
+            sum_size(self.bids.iter().filter(|level| level.price >= floor))
+        });
+        let asks = mid.or(self.best_ask()).map(|reference| {
+            let ceiling = reference * (1.0 + band);
+            sum_size(self.asks.iter().filter(|level| level.price <= ceiling))
+        });
+
+        BookDepth {
+            percent,
+            bids,
+            asks,
+        }
+    }
+}
+
+fn sum_size<'a>(levels: impl Iterator<Item = &'a OrderBookLevel>) -> f64 {
+    levels.map(|level| level.size).sum()
+}
+
+/// Liquidity resting near the top of an order book, see [`OrderBook::depth_within`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BookDepth {
+    /// Width of the band around the mid price, in percent
+    pub percent: f64,
+    pub bids: Option<f64>,
+    pub asks: Option<f64>,
+}
+
+/// Best bid and ask for one outcome of a market
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopOfBook {
+    pub outcome: String,
+    pub best_bid: Option<f64>,
+    pub best_ask: Option<f64>,
+    pub mid: Option<f64>,
+}
+
+impl TopOfBook {
+    /// An entry with no book data, for markets or outcomes without an order book
+    #[must_use]
+    pub fn empty(outcome: impl Into<String>) -> Self {
+        Self {
+            outcome: outcome.into(),
+            best_bid: None,
+            best_ask: None,
+            mid: None,
+        }
+    }
+
+    #[must_use]
+    pub fn from_book(book: &OrderBook) -> Self {
+        Self {
+            outcome: book.outcome_id.clone(),
+            best_bid: book.best_bid(),
+            best_ask: book.best_ask(),
+            mid: book.mid(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OrderBookLevel {
+    // The CLOB returns price levels as strings
+    #[serde(deserialize_with = "deserialize_string_or_number_to_f64")]
     pub price: f64,
+    #[serde(deserialize_with = "deserialize_string_or_number_to_f64")]
     pub size: f64,
 }
 
+/// Raw order book payload returned by the CLOB `/book` endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderBookResponse {
+    #[serde(default)]
+    pub market: Option<String>,
+    #[serde(default)]
+    pub asset_id: Option<String>,
+    #[serde(default)]
+    pub bids: Vec<OrderBookLevel>,
+    #[serde(default)]
+    pub asks: Vec<OrderBookLevel>,
+}
+
+/// Why a set of prices was flagged as an arbitrage opportunity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArbitrageKind {
+    /// Both outcomes of one binary market cost less than 1.0 combined
+    Underpriced,
+    /// Equivalent questions are priced apart across markets
+    Divergence,
+}
+
+/// A combination of positions that pays out 1.0 for less than 1.0
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArbitrageOpportunity {
+    pub kind: ArbitrageKind,
+    pub market_ids: Vec<String>,
+    /// Outcome to buy in each of `market_ids`, in the same order
+    pub outcomes: Vec<String>,
+    pub prices: Vec<f64>,
+    /// Guaranteed payout minus cost, per share, before fees
+    pub implied_profit: f64,
+    pub description: String,
+}
+
+/// Liquidity bucket derived from the configured analytics thresholds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LiquidityTier {
+    Low,
+    Medium,
+    High,
+}
+
+impl LiquidityTier {
+    pub fn from_liquidity(liquidity: f64, medium_threshold: f64, high_threshold: f64) -> Self {
+        if liquidity >= high_threshold {
+            Self::High
+        } else if liquidity >= medium_threshold {
+            Self::Medium
+        } else {
+            Self::Low
+        }
+    }
+}
+
+/// Deterministic sentiment signals for a market
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Sentiment {
+    pub market_id: String,
+    /// Outcome with the highest price, if any prices are available
+    pub favorite_outcome: Option<String>,
+    pub favorite_probability: Option<f64>,
+    pub liquidity: f64,
+    pub liquidity_tier: LiquidityTier,
+    /// Best ask minus best bid on the favorite outcome's order book
+    pub spread: Option<f64>,
+}
+
+impl Sentiment {
+    /// Builds sentiment from a market's quoted prices, using `spread` from its order book.
+    pub fn from_market(
+        market: &Market,
+        liquidity_tier: LiquidityTier,
+        spread: Option<f64>,
+    ) -> Self {
+        let favorite = market.favorite_outcome();
+
+        Self {
+            market_id: market.id.clone(),
+            favorite_outcome: favorite.map(|(outcome, _)| outcome.to_string()),
+            favorite_probability: favorite.map(|(_, price)| price),
+            liquidity: market.liquidity,
+            liquidity_tier,
+            spread,
+        }
+    }
+}
+
+/// Outcome of fetching several markets at once
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BatchResult {
+    /// Markets that were fetched, in request order
+    pub found: Vec<Market>,
+    /// `(market id, error message)` for each id that could not be fetched
+    pub failed: Vec<(String, String)>,
+}
+
+/// Platform-wide aggregates over a sample of active markets
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarketOverview {
+    /// Number of markets requested; the totals cover only this many, not every market
+    pub sample_size: u32,
+    /// Active markets actually aggregated, at most `sample_size`
+    pub active_markets: usize,
+    pub total_liquidity: f64,
+    pub total_volume_24h: f64,
+    pub median_liquidity: f64,
+    /// Most common category, ties broken alphabetically
+    pub top_category: Option<String>,
+    pub top_category_count: usize,
+}
+
+impl MarketOverview {
+    #[must_use]
+    pub fn from_markets(markets: &[Market], sample_size: u32) -> Self {
+        let mut liquidity: Vec<f64> = markets.iter().map(|m| m.liquidity).collect();
+        liquidity.sort_by(f64::total_cmp);
+        let median_liquidity = match liquidity.len() {
+            0 => 0.0,
+            n if n % 2 == 0 => (liquidity[n / 2 - 1] + liquidity[n / 2]) / 2.0,
+            n => liquidity[n / 2],
+        };
+
+        let mut categories: std::collections::BTreeMap<&str, usize> = Default::default();
+        for category in markets
+            .iter()
+            .filter_map(|m| m.category.as_deref())
+            .filter(|c| !c.trim().is_empty())
+        {
+            *categories.entry(category).or_default() += 1;
+        }
+        // BTreeMap iterates alphabetically; keep the first of equally common categories
+        let top =
+            categories
+                .into_iter()
+                .fold(
+                    None,
+                    |best: Option<(&str, usize)>, (category, count)| match best {
+                        Some((_, best_count)) if best_count >= count => best,
+                        _ => Some((category, count)),
+                    },
+                );
+
+        Self {
+            sample_size,
+            active_markets: markets.len(),
+            total_liquidity: liquidity.iter().sum(),
+            total_volume_24h: markets.iter().filter_map(|m| m.volume_24hr).sum(),
+            median_liquidity,
+            top_category: top.map(|(category, _)| category.to_string()),
+            top_category_count: top.map_or(0, |(_, count)| count),
+        }
+    }
+}
+
+/// A market related to another, with how strongly they are related
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimilarMarket {
+    pub market: Market,
+    /// Question term overlap plus bonuses for a shared event or category
+    pub similarity: f64,
+    /// Question terms both markets contain
+    pub shared_terms: Vec<String>,
+}
+
+/// Result of probing upstream API connectivity
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthStatus {
+    /// The API answered with a parseable market list
+    pub ok: bool,
+    pub latency_ms: f64,
+    /// An HTTP response was received, even if it was an error status
+    pub api_reachable: bool,
+    pub cache_enabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MarketStats {
     pub market_id: String,
@@ -233,6 +789,80 @@ pub struct MarketStats {
     pub num_traders: Option<u64>,
 }
 
+impl MarketStats {
+    /// Derives 24h statistics for a market's primary (first) outcome from its recent trades.
+    ///
+    /// Trades on the second outcome of a binary market are converted to the primary
+    /// outcome's price (`1 - price`). With no trades in the window, high/low fall back
+    /// to the current price and `num_traders` is `None`.
+    #[must_use]
+    pub fn from_trades(market: &Market, trades: &[Trade], now: DateTime<Utc>) -> Self {
+        let window_start = now - chrono::Duration::hours(24);
+        let primary = market.outcomes.first();
+        let current_price = market
+            .outcome_prices
+            .first()
+            .and_then(|p| p.parse::<f64>().ok())
+            .unwrap_or(0.0);
+
+        let mut recent: Vec<(DateTime<Utc>, &Trade)> = trades
+            .iter()
+            .filter_map(|trade| {
+                DateTime::parse_from_rfc3339(&trade.timestamp)
+                    .ok()
+                    .map(|ts| (ts.with_timezone(&Utc), trade))
+            })
+            .filter(|(ts, _)| *ts >= window_start && *ts <= now)
+            .collect();
+        recent.sort_by_key(|(ts, _)| *ts);
+
+        let primary_prices: Vec<f64> = recent
+            .iter()
+            .map(|(_, trade)| {
+                let is_primary = trade.outcome_id.is_empty()
+                    || primary.is_some_and(|p| p.eq_ignore_ascii_case(&trade.outcome_id));
+                if is_primary || market.outcomes.len() != 2 {
+                    trade.price
+                } else {
+                    1.0 - trade.price
+                }
+            })
+            .collect();
+
+        let trade_volume: f64 = recent
+            .iter()
+            .map(|(_, trade)| trade.size * trade.price)
+            .sum();
+
+        let (high_24h, low_24h, price_change_24h) = match primary_prices.first() {
+            Some(&opening) => (
+                primary_prices.iter().copied().fold(f64::MIN, f64::max),
+                primary_prices.iter().copied().fold(f64::MAX, f64::min),
+                current_price - opening,
+            ),
+            None => (current_price, current_price, 0.0),
+        };
+
+        let num_traders = (!recent.is_empty()).then(|| {
+            recent
+                .iter()
+                .filter_map(|(_, trade)| trade.trader_address.as_deref())
+                .collect::<std::collections::HashSet<_>>()
+                .len() as u64
+        });
+
+        Self {
+            market_id: market.id.clone(),
+            volume_24h: market.volume_24hr.unwrap_or(trade_volume),
+            price_change_24h,
+            high_24h,
+            low_24h,
+            liquidity: market.liquidity,
+            num_traders,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApiError {
     pub error: String,
@@ -377,6 +1007,7 @@ pub struct McpPrompt {
 pub struct McpPromptArgument {
     pub name: String,
     pub description: String,
+    #[serde(default)]
     pub required: bool,
 }
 
@@ -429,16 +1060,88 @@ fn deserialize_string_to_f64<'de, D>(deserializer: D) -> Result<f64, D::Error>
 where
     D: Deserializer<'de>,
 {
-    let s: String = Deserialize::deserialize(deserializer)?;
-    s.parse::<f64>().map_err(serde::de::Error::custom)
+    use serde_json::Value;
+    // Liquidity and volume arrive as "" for markets with no activity
+    match Value::deserialize(deserializer)? {
+        Value::String(s) if s.trim().is_empty() => Ok(0.0),
+        value => deserialize_string_or_number_to_f64(value).map_err(serde::de::Error::custom),
+    }
 }
 
 fn deserialize_json_string_to_vec<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
 where
     D: Deserializer<'de>,
 {
-    let s: String = Deserialize::deserialize(deserializer)?;
-    serde_json::from_str(&s).map_err(serde::de::Error::custom)
+    // Arrays come from some endpoints and from re-reading our own serialized
+    // output, such as the persisted cache
+    deserialize_optional_json_string_to_vec(deserializer)?
+        .ok_or_else(|| serde::de::Error::custom("Expected JSON string or array"))
+}
+
+fn deserialize_string_or_number_to_f64<'de, D>(deserializer: D) -> Result<f64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    use serde_json::Value;
+    match Value::deserialize(deserializer)? {
+        Value::String(s) => s
+            .trim()
+            .parse::<f64>()
+            .map_err(|_| serde::de::Error::custom(format!("Invalid numeric string '{s}'"))),
+        Value::Number(n) => n
+            .as_f64()
+            .ok_or_else(|| serde::de::Error::custom("Invalid number")),
+        other => Err(serde::de::Error::custom(format!(
+            "Expected string or number, got {other}"
+        ))),
+    }
+}
+
+fn deserialize_timestamp_to_rfc3339<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    use serde_json::Value;
+    // Unix timestamps (seconds) are normalized to RFC3339, strings are kept as-is
+    match Value::deserialize(deserializer)? {
+        Value::String(s) => Ok(s),
+        Value::Number(n) => n
+            .as_i64()
+            .and_then(|secs| chrono::DateTime::from_timestamp(secs, 0))
+            .map(|dt| dt.to_rfc3339())
+            .ok_or_else(|| serde::de::Error::custom("Invalid unix timestamp")),
+        _ => Err(serde::de::Error::custom("Expected string or number")),
+    }
+}
+
+fn deserialize_optional_json_string_to_vec<'de, D>(
+    deserializer: D,
+) -> Result<Option<Vec<String>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    use serde_json::Value;
+    // Gamma usually JSON-encodes these lists, but some endpoints return real arrays
+    let items = match Option::<Value>::deserialize(deserializer)? {
+        Some(Value::String(s)) => {
+            serde_json::from_str::<Vec<Value>>(&s).map_err(serde::de::Error::custom)?
+        }
+        Some(Value::Array(items)) => items,
+        Some(_) => return Err(serde::de::Error::custom("Expected JSON string or array")),
+        None => return Ok(None),
+    };
+
+    items
+        .into_iter()
+        .map(|item| match item {
+            Value::String(s) => Ok(s),
+            Value::Number(n) => Ok(n.to_string()),
+            other => Err(serde::de::Error::custom(format!(
+                "Expected string or number in list, got {other}"
+            ))),
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map(Some)
 }
 
 fn deserialize_optional_string_or_number_to_f64<'de, D>(
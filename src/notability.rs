@@ -0,0 +1,91 @@
+//! Ranking of markets by how noteworthy they are right now, rather than by sheer size.
+
+use crate::config::AnalyticsConfig;
+use crate::models::Market;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Hours to close at which the time component has decayed to one half
+const TIME_HALF_LIFE_HOURS: f64 = 168.0;
+
+/// The normalized signals behind a notability score, each in `[0, 1]`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct NotabilityComponents {
+    /// Share of the market's lifetime volume traded in the last 24 hours
+    pub volume_surge: f64,
+    /// Liquidity on a log scale relative to the most liquid candidate
+    pub liquidity: f64,
+    /// Nearness of the end date; 0.5 a week out, 0 once it has passed
+    pub time_to_close: f64,
+}
+
+/// A market with its notability score
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotableMarket {
+    pub market: Market,
+    /// Weighted sum of the components, using the `analytics.notability_*_weight` settings
+    pub score: f64,
+    pub components: NotabilityComponents,
+}
+
+impl NotabilityComponents {
+    /// Computes the components for `market`, with liquidity scaled against `max_liquidity`.
+    #[must_use]
+    pub fn for_market(market: &Market, max_liquidity: f64, now: DateTime<Utc>) -> Self {
+        let volume_surge = match market.volume_24hr {
+            Some(recent) if market.volume > 0.0 => (recent / market.volume).clamp(0.0, 1.0),
+            _ => 0.0,
+        };
+        let liquidity = if max_liquidity > 0.0 {
+            (market.liquidity.max(0.0).ln_1p() / max_liquidity.ln_1p()).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        let time_to_close = match market.time_to_resolution_hours(now) {
+            Some(hours) if hours > 0.0 => TIME_HALF_LIFE_HOURS / (TIME_HALF_LIFE_HOURS + hours),
+            _ => 0.0,
+        };
+
+        Self {
+            volume_surge,
+            liquidity,
+            time_to_close,
+        }
+    }
+
+    #[must_use]
+    pub fn score(&self, analytics: &AnalyticsConfig) -> f64 {
+        self.volume_surge * analytics.notability_volume_weight
+            + self.liquidity * analytics.notability_liquidity_weight
+            + self.time_to_close * analytics.notability_time_weight
+    }
+}
+
+/// Scores `markets` and returns the `limit` highest, best first.
+#[must_use]
+pub fn rank(
+    markets: Vec<Market>,
+    analytics: &AnalyticsConfig,
+    now: DateTime<Utc>,
+    limit: usize,
+) -> Vec<NotableMarket> {
+    let max_liquidity = markets
+        .iter()
+        .map(|market| market.liquidity)
+        .fold(0.0, f64::max);
+
+    let mut ranked: Vec<NotableMarket> = markets
+        .into_iter()
+        .map(|market| {
+            let components = NotabilityComponents::for_market(&market, max_liquidity, now);
+            NotableMarket {
+                score: components.score(analytics),
+                components,
+                market,
+            }
+        })
+        .collect();
+    ranked.sort_by(|a, b| b.score.total_cmp(&a.score));
+    ranked.truncate(limit);
+    ranked
+}
@@ -0,0 +1,176 @@
+//! Circuit breaker that fails fast while the upstream API is down.
+
+use crate::error::{PolymarketError, Result};
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+/// Whether requests are currently allowed through to the upstream API.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    /// Requests flow normally
+    #[default]
+    Closed,
+    /// Too many consecutive failures; requests fail fast until the cooldown ends
+    Open,
+    /// The cooldown ended and a single probe request is testing recovery
+    HalfOpen,
+}
+
+#[derive(Debug, Default)]
+pub(super) struct BreakerState {
+    state: CircuitState,
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+}
+
+impl BreakerState {
+    pub(super) fn state(&self) -> CircuitState {
+        self.state
+    }
+
+    /// Decides whether a request may go upstream.
+    ///
+    /// Once the cooldown of an open circuit has elapsed exactly one probe is let
+    /// through; other requests keep failing fast until that probe settles. A probe
+    /// that never reports back (e.g. its caller was cancelled) is replaced after
+    /// another cooldown. A `threshold` of 0 disables the breaker.
+    pub(super) fn admit(&mut self, threshold: u32, cooldown: Duration) -> Result<()> {
+        if threshold == 0 {
+            return Ok(());
+        }
+
+        if self.state == CircuitState::Closed {
+            return Ok(());
+        }
+
+        // While open this is the time since opening; while half-open, since the probe
+        let elapsed = self.opened_at.map_or(cooldown, |at| at.elapsed());
+        if elapsed >= cooldown {
+            self.state = CircuitState::HalfOpen;
+            self.opened_at = Some(Instant::now());
+            return Ok(());
+        }
+
+        Err(match self.state {
+            CircuitState::HalfOpen => PolymarketError::network_error(
+                "Circuit breaker half-open; waiting for a recovery probe to finish",
+            ),
+            _ => PolymarketError::network_error(format!(
+                "Circuit breaker open after {} consecutive failures; retrying upstream in {}s",
+                self.consecutive_failures,
+                (cooldown - elapsed).as_secs().max(1)
+            )),
+        })
+    }
+
+    /// Records the outcome of an admitted request.
+    ///
+    /// Errors suggesting the upstream is unavailable or returning garbage count as
+    /// failures; a 404 still proves the API is answering.
+    pub(super) fn record<T>(&mut self, threshold: u32, outcome: &Result<T>) {
+        if threshold == 0 {
+            return;
+        }
+
+        let failed = outcome.as_ref().err().is_some_and(indicates_outage);
+        if !failed {
+            self.state = CircuitState::Closed;
+            self.consecutive_failures = 0;
+            self.opened_at = None;
+            return;
+        }
+
+        self.consecutive_failures += 1;
+        if self.state == CircuitState::HalfOpen || self.consecutive_failures >= threshold {
+            if self.state != CircuitState::Open {
+                tracing::warn!(
+                    "Circuit breaker opened after {} consecutive failures",
+                    self.consecutive_failures
+                );
+            }
+            self.state = CircuitState::Open;
+            self.opened_at = Some(Instant::now());
+        }
+    }
+}
+
+/// Picks the state to report for several breakers: open if any is open, otherwise
+/// half-open if any is probing, otherwise closed.
+pub(super) fn most_degraded(states: impl IntoIterator<Item = CircuitState>) -> CircuitState {
+    states
+        .into_iter()
+        .fold(CircuitState::Closed, |worst, state| match (worst, state) {
+            (CircuitState::Open, _) | (_, CircuitState::Open) => CircuitState::Open,
+            (CircuitState::HalfOpen, _) | (_, CircuitState::HalfOpen) => CircuitState::HalfOpen,
+            _ => CircuitState::Closed,
+        })
+}
+
+fn indicates_outage(error: &PolymarketError) -> bool {
+    match error {
+        PolymarketError::Network { .. } | PolymarketError::Deserialization { .. } => true,
+        PolymarketError::Api { status_code, .. } => {
+            status_code.is_none_or(|status| status >= 500 || status == 429)
+        }
+        PolymarketError::NotFound { .. }
+        | PolymarketError::Config { .. }
+        | PolymarketError::InvalidArgument { .. }
+        | PolymarketError::Resource { .. }
+        | PolymarketError::Internal { .. }
+        | PolymarketError::Io { .. } => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outage() -> Result<()> {
+        Err(PolymarketError::network_error("connection refused"))
+    }
+
+    #[test]
+    fn test_opens_after_threshold_and_recovers_through_half_open() {
+        let mut breaker = BreakerState::default();
+        let cooldown = Duration::from_secs(60);
+
+        breaker.record(2, &outage());
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        breaker.record(2, &outage());
+        assert_eq!(breaker.state(), CircuitState::Open);
+        assert!(breaker.admit(2, cooldown).is_err());
+
+        // Cooldown elapsed: one probe goes through, concurrent requests still fail fast
+        assert!(breaker.admit(2, Duration::ZERO).is_ok());
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+        assert!(breaker.admit(2, cooldown).is_err());
+
+        // A failed probe reopens immediately; a successful one closes the circuit
+        breaker.record(2, &outage());
+        assert_eq!(breaker.state(), CircuitState::Open);
+        assert!(breaker.admit(2, Duration::ZERO).is_ok());
+        breaker.record(2, &Ok(()));
+        assert_eq!(breaker.state(), CircuitState::Closed);
+    }
+
+    #[test]
+    fn test_client_errors_do_not_trip_breaker() {
+        let mut breaker = BreakerState::default();
+        for _ in 0..5 {
+            breaker.record::<()>(1, &Err(PolymarketError::api_error("missing", Some(404))));
+        }
+        assert_eq!(breaker.state(), CircuitState::Closed);
+
+        breaker.record(0, &outage());
+        assert_eq!(breaker.state(), CircuitState::Closed);
+    }
+
+    #[test]
+    fn test_most_degraded_prefers_open_then_half_open() {
+        use CircuitState::{Closed, HalfOpen, Open};
+        assert_eq!(most_degraded([]), Closed);
+        assert_eq!(most_degraded([Closed, HalfOpen, Closed]), HalfOpen);
+        assert_eq!(most_degraded([HalfOpen, Open, Closed]), Open);
+    }
+}
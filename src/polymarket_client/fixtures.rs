@@ -0,0 +1,71 @@
+//! Offline mode: serving API responses from JSON fixture files instead of the network.
+
+use super::{json_path, PolymarketClient};
+use crate::config::Config;
+use crate::error::{PolymarketError, Result};
+use std::path::Path;
+
+impl PolymarketClient {
+    /// Reads the fixture standing in for a GET of `url` from `api.fixtures_dir`.
+    ///
+    /// A missing fixture is an error; offline mode never falls back to the network.
+    pub(super) async fn read_fixture<T: serde::de::DeserializeOwned>(
+        &self,
+        config: &Config,
+        url: &str,
+    ) -> Result<T> {
+        let name = fixture_name(self.endpoint_path(url));
+        let path = Path::new(&config.api.fixtures_dir).join(&name);
+        let text = std::fs::read_to_string(&path).map_err(|e| {
+            PolymarketError::config_error(format!(
+                "Offline mode: no fixture for {url} at {} ({e})",
+                path.display()
+            ))
+        })?;
+
+        json_path::parse(&text).map_err(|e| {
+            PolymarketError::deserialization_error(format!("{e} in {}", path.display()))
+        })
+    }
+
+    /// Returns the path of `url` relative to whichever configured host it targets,
+    /// without the query string.
+    pub(super) fn endpoint_path<'a>(&self, url: &'a str) -> &'a str {
+        let url = url.split('?').next().unwrap_or(url);
+        [&self.base_url, &self.clob_url, &self.data_api_url]
+            .into_iter()
+            .find_map(|base| url.strip_prefix(base.as_str()))
+            .unwrap_or(url)
+    }
+}
+
+/// Maps an endpoint path to its fixture file name.
+///
+/// A collection and one of its members map to `markets.json` and `market_<id>.json`;
+/// any other path joins its segments with `_`, e.g. `/prices-history` becomes
+/// `prices-history.json`. Query parameters are ignored.
+fn fixture_name(path: &str) -> String {
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    let stem = match segments.as_slice() {
+        [] => "index".to_string(),
+        [collection, id] if collection.ends_with('s') => {
+            format!("{}_{id}", &collection[..collection.len() - 1])
+        }
+        segments => segments.join("_"),
+    };
+    format!("{stem}.json")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixture_names() {
+        assert_eq!(fixture_name("/markets"), "markets.json");
+        assert_eq!(fixture_name("/markets/123"), "market_123.json");
+        assert_eq!(fixture_name("/events/e1"), "event_e1.json");
+        assert_eq!(fixture_name("/prices-history"), "prices-history.json");
+        assert_eq!(fixture_name("/"), "index.json");
+    }
+}
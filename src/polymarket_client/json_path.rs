@@ -0,0 +1,101 @@
+//! Locating the JSON path of a deserialization failure within a response body.
+
+use serde::de::DeserializeOwned;
+
+/// Maximum number of body characters echoed back in a deserialization error
+const SNIPPET_CHARS: usize = 200;
+
+/// Parses `text`, describing a failure by the failing field path, e.g.
+/// `[3].outcomePrices`, followed by serde's message and a snippet of the body.
+pub(super) fn parse<T: DeserializeOwned>(text: &str) -> Result<T, String> {
+    let deserializer = &mut serde_json::Deserializer::from_str(text);
+    serde_path_to_error::deserialize(deserializer).map_err(|e| {
+        let snippet: String = text.chars().take(SNIPPET_CHARS).collect();
+        let ellipsis = if text.chars().nth(SNIPPET_CHARS).is_some() {
+            "..."
+        } else {
+            ""
+        };
+        format!(
+            "JSON parsing error at {}: {} (body: {snippet}{ellipsis})",
+            path_name(e.path()),
+            e.inner()
+        )
+    })
+}
+
+/// Renders a path, naming the document root `$` rather than `.`.
+pub(super) fn path_name(path: &serde_path_to_error::Path) -> String {
+    match path.to_string() {
+        root if root == "." => "$".to_string(),
+        path => path,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Market;
+
+    fn error_message<T: DeserializeOwned + std::fmt::Debug>(text: &str) -> String {
+        parse::<T>(text).unwrap_err()
+    }
+
+    #[test]
+    fn test_path_names_failing_array_element_field() {
+        let good = r#"{"id":"1","slug":"a","question":"q","active":true,"closed":false,"liquidity":"1","volume":"1","endDate":"2030","outcomes":"[]","outcomePrices":"[]"}"#;
+        let bad = r#"{"id":"2","slug":"b","question":"q","active":true,"closed":false,"liquidity":"1","volume":"1","endDate":"2030","outcomes":"[]","outcomePrices":"[]","acceptingOrders":"yes"}"#;
+        let text = format!("[{good},\n{good},\n{bad}]");
+
+        let message = error_message::<Vec<Market>>(&text);
+
+        assert!(
+            message.starts_with("JSON parsing error at [2].acceptingOrders:"),
+            "{message}"
+        );
+        assert!(message.contains("(body: ["));
+
+        let bad = good.replace(r#""outcomePrices":"[]""#, r#""outcomePrices":{"x":1}"#);
+        let message = error_message::<Vec<Market>>(&format!("[{good},\n{bad}]"));
+        assert!(
+            message.starts_with("JSON parsing error at [1].outcomePrices:"),
+            "{message}"
+        );
+
+        let bad = good.replace(r#""id":"1","#, "");
+        let message = error_message::<Vec<Market>>(&format!("[{good},{bad}]"));
+        assert!(
+            message.starts_with("JSON parsing error at [1]: missing field `id`"),
+            "{message}"
+        );
+    }
+
+    #[test]
+    fn test_path_for_nested_objects_and_root() {
+        #[derive(Debug, serde::Deserialize)]
+        #[allow(dead_code)]
+        struct Inner {
+            value: u32,
+        }
+        #[derive(Debug, serde::Deserialize)]
+        #[allow(dead_code)]
+        struct Outer {
+            name: String,
+            inner: Inner,
+        }
+
+        let message = error_message::<Outer>(r#"{"name":"x","inner":{"value":"oops"}}"#);
+        assert!(message.starts_with("JSON parsing error at inner.value:"));
+
+        let message = error_message::<Outer>(r#""not an object""#);
+        assert!(message.starts_with("JSON parsing error at $:"), "{message}");
+    }
+
+    #[test]
+    fn test_snippet_is_truncated() {
+        let text = format!("[\"{}\"]", "x".repeat(500));
+        let message = error_message::<Vec<u32>>(&text);
+        let body = message.split("(body: ").nth(1).unwrap();
+        assert_eq!(body.chars().count(), SNIPPET_CHARS + "...)".len());
+    }
+}
@@ -0,0 +1,209 @@
+//! On-disk persistence of the market caches across restarts.
+
+use super::{CacheEntry, LruCache, PolymarketClient};
+use crate::config::Config;
+use crate::error::{PolymarketError, Result};
+use crate::models::Market;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, UNIX_EPOCH};
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PersistedEntry<T> {
+    /// Unix timestamp (milliseconds) at which the entry was cached
+    cached_at_ms: u64,
+    data: T,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PersistedCaches {
+    #[serde(default)]
+    markets: HashMap<String, PersistedEntry<Vec<Market>>>,
+    #[serde(default)]
+    single_markets: HashMap<String, PersistedEntry<Market>>,
+}
+
+impl PolymarketClient {
+    /// Writes the market caches to `cache.persist_path`, if configured.
+    ///
+    /// The caches are written to a temporary file next to it and renamed into place,
+    /// so an interrupted save leaves the previous file intact.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the caches cannot be serialized or the file cannot be written.
+    pub async fn save_cache(&self) -> Result<()> {
+        let config = self.config();
+        let Some(path) = config.cache.persist_path.as_deref() else {
+            return Ok(());
+        };
+
+        let caches = PersistedCaches {
+            markets: to_persisted(&*self.market_cache.read().await),
+            single_markets: to_persisted(&*self.single_market_cache.read().await),
+        };
+        let json = serde_json::to_vec(&caches).map_err(|e| {
+            PolymarketError::deserialization_error(format!("Failed to serialize cache: {e}"))
+        })?;
+        let temp_path = format!("{path}.tmp");
+        tokio::fs::write(&temp_path, json)
+            .await
+            .map_err(|e| PolymarketError::io_error(&temp_path, &e))?;
+        tokio::fs::rename(&temp_path, path)
+            .await
+            .map_err(|e| PolymarketError::io_error(path, &e))?;
+
+        tracing::info!(
+            "Persisted {} market list and {} market cache entries to {}",
+            caches.markets.len(),
+            caches.single_markets.len(),
+            path
+        );
+        Ok(())
+    }
+}
+
+/// Builds the market caches, seeding them from `cache.persist_path` when it exists.
+///
+/// Entries already past the cache TTL are discarded. A missing or unreadable file only
+/// logs a warning, since persistence is an optimization for cold starts.
+pub(super) fn load_caches(config: &Config) -> (LruCache<Vec<Market>>, LruCache<Market>) {
+    let mut market_cache = LruCache::new(config.cache.max_entries);
+    let mut single_market_cache = LruCache::new(config.cache.max_entries);
+
+    let Some(path) = config.cache.persist_path.as_deref() else {
+        return (market_cache, single_market_cache);
+    };
+    let caches = match std::fs::read(path) {
+        Ok(bytes) => match serde_json::from_slice::<PersistedCaches>(&bytes) {
+            Ok(caches) => caches,
+            Err(e) => {
+                tracing::warn!("Ignoring unreadable cache file {}: {}", path, e);
+                return (market_cache, single_market_cache);
+            }
+        },
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return (market_cache, single_market_cache);
+        }
+        Err(e) => {
+            tracing::warn!("Failed to read cache file {}: {}", path, e);
+            return (market_cache, single_market_cache);
+        }
+    };
+
+    let ttl = config.cache_ttl();
+    restore(&mut market_cache, caches.markets, ttl);
+    restore(&mut single_market_cache, caches.single_markets, ttl);
+    tracing::info!(
+        "Restored {} market list and {} market cache entries from {}",
+        market_cache.len(),
+        single_market_cache.len(),
+        path
+    );
+
+    (market_cache, single_market_cache)
+}
+
+fn to_persisted<T: Clone>(cache: &LruCache<T>) -> HashMap<String, PersistedEntry<T>> {
+    cache
+        .iter()
+        .map(|(key, entry)| {
+            let cached_at_ms = entry
+                .timestamp
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_millis() as u64);
+            let persisted = PersistedEntry {
+                cached_at_ms,
+                data: entry.data.clone(),
+            };
+            (key.clone(), persisted)
+        })
+        .collect()
+}
+
+fn restore<T>(cache: &mut LruCache<T>, entries: HashMap<String, PersistedEntry<T>>, ttl: Duration) {
+    for (key, persisted) in entries {
+        let entry = CacheEntry {
+            data: persisted.data,
+            timestamp: UNIX_EPOCH + Duration::from_millis(persisted.cached_at_ms),
+        };
+        if !entry.is_expired(ttl) {
+            cache.insert_entry(key, entry);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::time::SystemTime;
+
+    fn market(id: &str) -> Market {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "slug": format!("market-{id}"),
+            "question": "Will it happen?",
+            "active": true,
+            "closed": false,
+            "liquidity": "1000",
+            "volume": "5000",
+            "endDate": "2030-01-01T00:00:00Z",
+            "outcomes": "[\"Yes\", \"No\"]",
+            "outcomePrices": "[\"0.6\", \"0.4\"]"
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_cache_round_trips_through_disk() {
+        let path = std::env::temp_dir().join(format!(
+            "polymarket-mcp-cache-{}.json",
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        let mut config = Config::default();
+        config.cache.persist_path = Some(path.to_string_lossy().into_owned());
+        let config = Arc::new(config);
+
+        let client = PolymarketClient::new_with_config(&config).unwrap();
+        client
+            .single_market_cache
+            .write()
+            .await
+            .insert("1".to_string(), market("1"));
+        client.single_market_cache.write().await.insert_entry(
+            "stale".to_string(),
+            CacheEntry {
+                data: market("stale"),
+                timestamp: UNIX_EPOCH,
+            },
+        );
+        client.save_cache().await.unwrap();
+        assert!(!std::path::Path::new(&format!("{}.tmp", path.display())).exists());
+
+        let restored = PolymarketClient::new_with_config(&config).unwrap();
+        let mut cache = restored.single_market_cache.write().await;
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(cache.len(), 1);
+        let entry = cache.get("1").unwrap();
+        assert_eq!(entry.data.outcomes, vec!["Yes", "No"]);
+        assert_eq!(entry.data.liquidity, 1000.0);
+    }
+
+    #[tokio::test]
+    async fn test_save_reports_io_errors() {
+        let mut config = Config::default();
+        config.cache.persist_path = Some("/nonexistent-dir/cache.json".to_string());
+        let client = PolymarketClient::new_with_config(&Arc::new(config)).unwrap();
+
+        let error = client.save_cache().await.unwrap_err();
+        assert!(matches!(error, PolymarketError::Io { .. }), "{error}");
+        assert!(error
+            .to_string()
+            .contains("/nonexistent-dir/cache.json.tmp"));
+    }
+}
@@ -0,0 +1,161 @@
+//! Rendering of [`Metrics`] in the Prometheus text exposition format.
+
+use super::{CircuitState, Metrics};
+use std::fmt::Write;
+
+impl Metrics {
+    /// Renders the metrics in Prometheus text exposition format (version 0.0.4).
+    ///
+    /// Counters only ever grow for the life of the process. Cache sizes are gauges, and
+    /// the circuit breaker state is a gauge with one series per state, set to 1 for the
+    /// current one, both overall and for each upstream host.
+    #[must_use]
+    pub fn to_prometheus(&self) -> String {
+        let mut out = String::new();
+        let counters = [
+            (
+                "polymarket_api_requests_total",
+                "Upstream API requests, counting each request once regardless of retries",
+                self.api_requests_total,
+            ),
+            (
+                "polymarket_api_failures_total",
+                "Upstream API requests that failed after exhausting retries",
+                self.api_failures,
+            ),
+            (
+                "polymarket_cache_hits_total",
+                "Lookups served from cache",
+                self.cache_hits,
+            ),
+            (
+                "polymarket_cache_misses_total",
+                "Lookups that missed the cache",
+                self.cache_misses,
+            ),
+            (
+                "polymarket_markets_skipped_total",
+                "Markets dropped from list responses because they failed to deserialize",
+                self.markets_skipped,
+            ),
+            (
+                "polymarket_coalesced_requests_total",
+                "Requests served by sharing an identical request already in flight",
+                self.coalesced_requests,
+            ),
+        ];
+        for (name, help, value) in counters {
+            let _ = writeln!(out, "# HELP {name} {help}");
+            let _ = writeln!(out, "# TYPE {name} counter");
+            let _ = writeln!(out, "{name} {value}");
+        }
+
+        let name = "polymarket_api_request_duration_ms";
+        let _ = writeln!(
+            out,
+            "# HELP {name} Upstream API request latency including retries, in milliseconds"
+        );
+        let _ = writeln!(out, "# TYPE {name} summary");
+        let _ = writeln!(out, "{name}_sum {}", self.response_time_ms_total);
+        let _ = writeln!(out, "{name}_count {}", self.api_requests_total);
+
+        let name = "polymarket_tool_calls_total";
+        let _ = writeln!(out, "# HELP {name} MCP tools/call invocations by tool");
+        let _ = writeln!(out, "# TYPE {name} counter");
+        let mut tools: Vec<_> = self.tool_calls.iter().collect();
+        tools.sort();
+        for (tool, count) in tools {
+            let _ = writeln!(out, "{name}{{tool=\"{}\"}} {count}", escape_label(tool));
+        }
+
+        let name = "polymarket_cache_entries";
+        let _ = writeln!(out, "# HELP {name} Entries currently held in each cache");
+        let _ = writeln!(out, "# TYPE {name} gauge");
+        for (cache, size) in [
+            ("market", self.market_cache_size),
+            ("single_market", self.single_market_cache_size),
+            ("resource", self.resource_cache_size),
+        ] {
+            let _ = writeln!(out, "{name}{{cache=\"{cache}\"}} {size}");
+        }
+
+        let states = [
+            ("closed", CircuitState::Closed),
+            ("open", CircuitState::Open),
+            ("half_open", CircuitState::HalfOpen),
+        ];
+        let name = "polymarket_circuit_breaker_state";
+        let _ = writeln!(
+            out,
+            "# HELP {name} Most degraded upstream circuit breaker state; 1 for the current state"
+        );
+        let _ = writeln!(out, "# TYPE {name} gauge");
+        for (label, state) in states {
+            let value = u8::from(self.circuit_state == state);
+            let _ = writeln!(out, "{name}{{state=\"{label}\"}} {value}");
+        }
+
+        let name = "polymarket_circuit_breaker_host_state";
+        let _ = writeln!(
+            out,
+            "# HELP {name} Circuit breaker state per upstream host; 1 for the current state"
+        );
+        let _ = writeln!(out, "# TYPE {name} gauge");
+        let mut hosts: Vec<_> = self.circuit_states.iter().collect();
+        hosts.sort_by_key(|(host, _)| *host);
+        for (host, current) in hosts {
+            let host = escape_label(host);
+            for (label, state) in states {
+                let value = u8::from(*current == state);
+                let _ = writeln!(out, "{name}{{host=\"{host}\",state=\"{label}\"}} {value}");
+            }
+        }
+
+        out
+    }
+}
+
+/// Escapes a label value per the exposition format: backslash, quote and newline.
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prometheus_rendering() {
+        let mut metrics = Metrics {
+            api_requests_total: 4,
+            cache_hits: 3,
+            response_time_ms_total: 250.5,
+            circuit_state: CircuitState::Open,
+            circuit_states: [("clob.polymarket.com".to_string(), CircuitState::Open)].into(),
+            single_market_cache_size: 7,
+            ..Default::default()
+        };
+        metrics.increment_tool_call("get_metrics");
+        metrics.increment_tool_call("odd\"name");
+
+        let text = metrics.to_prometheus();
+        assert!(text.contains(
+            "# TYPE polymarket_api_requests_total counter\npolymarket_api_requests_total 4\n"
+        ));
+        assert!(text.contains("polymarket_cache_hits_total 3\n"));
+        assert!(text.contains("polymarket_api_request_duration_ms_sum 250.5\n"));
+        assert!(text.contains("polymarket_api_request_duration_ms_count 4\n"));
+        assert!(text.contains("polymarket_tool_calls_total{tool=\"get_metrics\"} 1\n"));
+        assert!(text.contains("polymarket_tool_calls_total{tool=\"odd\\\"name\"} 1\n"));
+        assert!(text.contains("# TYPE polymarket_cache_entries gauge\n"));
+        assert!(text.contains("polymarket_cache_entries{cache=\"single_market\"} 7\n"));
+        assert!(text.contains("polymarket_circuit_breaker_state{state=\"open\"} 1\n"));
+        assert!(text.contains("polymarket_circuit_breaker_state{state=\"closed\"} 0\n"));
+        assert!(text.contains(
+            "polymarket_circuit_breaker_host_state{host=\"clob.polymarket.com\",state=\"open\"} 1\n"
+        ));
+    }
+}
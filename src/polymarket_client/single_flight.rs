@@ -0,0 +1,71 @@
+//! Coalescing of concurrent identical requests into a single upstream call.
+
+use crate::error::Result;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast;
+
+/// A response body shared between every caller of a coalesced request
+pub(super) type Body = Arc<str>;
+
+/// Requests currently in flight, keyed by URL.
+#[derive(Debug, Default)]
+pub(super) struct InFlight {
+    requests: Mutex<HashMap<String, broadcast::Sender<Result<Body>>>>,
+}
+
+/// A caller's part in a request: the first caller for a URL fetches it, later ones
+/// wait for that result.
+pub(super) enum Role<'a> {
+    Leader(Leader<'a>),
+    Follower(broadcast::Receiver<Result<Body>>),
+}
+
+impl InFlight {
+    pub(super) fn join(&self, url: &str) -> Role<'_> {
+        let mut requests = self.requests.lock().unwrap();
+        if let Some(sender) = requests.get(url) {
+            return Role::Follower(sender.subscribe());
+        }
+
+        let (sender, _) = broadcast::channel(1);
+        requests.insert(url.to_string(), sender);
+        Role::Leader(Leader {
+            in_flight: self,
+            url: Some(url.to_string()),
+        })
+    }
+
+    fn remove(&self, url: &str) -> Option<broadcast::Sender<Result<Body>>> {
+        self.requests.lock().unwrap().remove(url)
+    }
+}
+
+/// Held by the caller that performs the upstream request. Dropping it without
+/// calling [`Leader::finish`] (e.g. when the caller is cancelled) releases the URL,
+/// and waiting followers fall back to fetching on their own.
+pub(super) struct Leader<'a> {
+    in_flight: &'a InFlight,
+    url: Option<String>,
+}
+
+impl Leader<'_> {
+    /// Hands `result` to every follower that joined while the request was in flight.
+    pub(super) fn finish(mut self, result: &Result<Body>) {
+        let Some(url) = self.url.take() else {
+            return;
+        };
+        if let Some(sender) = self.in_flight.remove(&url) {
+            // No receivers just means nobody else asked for this URL meanwhile
+            let _ = sender.send(result.clone());
+        }
+    }
+}
+
+impl Drop for Leader<'_> {
+    fn drop(&mut self) {
+        if let Some(url) = self.url.take() {
+            self.in_flight.remove(&url);
+        }
+    }
+}
@@ -0,0 +1,300 @@
+//! Live price subscriptions over the Polymarket CLOB WebSocket feed.
+
+use super::PolymarketClient;
+use crate::error::{PolymarketError, Result};
+use crate::models::MarketPrice;
+use futures::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+
+pub(super) const WS_MARKET_URL: &str = "wss://ws-subscriptions-clob.polymarket.com/ws/market";
+
+const CHANNEL_CAPACITY: usize = 256;
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+const PING_INTERVAL: Duration = Duration::from_secs(10);
+/// A connection must stay up this long before the reconnect backoff starts over
+const STABLE_CONNECTION: Duration = Duration::from_secs(60);
+
+/// Maps CLOB token ids to the `(market id, outcome label, outcome index)` they price.
+type TokenMap = HashMap<String, (String, String, usize)>;
+
+impl PolymarketClient {
+    /// Subscribes to live price updates for the given markets.
+    ///
+    /// Updates are forwarded on the returned channel as [`MarketPrice`] values. The
+    /// connection is re-established with exponential backoff (starting from
+    /// `config.retry_delay()`) whenever it drops; the backoff only resets once a
+    /// connection has stayed up for a while, so a feed that accepts and immediately
+    /// drops connections isn't hammered. The background task exits once the receiver
+    /// is dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - Any of the markets cannot be fetched
+    /// - None of the markets expose CLOB token ids
+    pub async fn subscribe_prices(
+        &self,
+        market_ids: Vec<String>,
+    ) -> Result<mpsc::Receiver<MarketPrice>> {
+        let mut tokens = TokenMap::new();
+        for market_id in &market_ids {
+            let market = self.get_market_by_id(market_id).await?;
+            let token_ids = market.clob_token_ids.unwrap_or_default();
+            for (index, (token_id, outcome)) in
+                token_ids.into_iter().zip(market.outcomes).enumerate()
+            {
+                tokens.insert(token_id, (market_id.clone(), outcome, index));
+            }
+        }
+
+        if tokens.is_empty() {
+            return Err(PolymarketError::api_error(
+                "None of the requested markets have live order books",
+                None,
+            ));
+        }
+
+        let (tx, rx) = mpsc::channel(CHANNEL_CAPACITY);
+        let url = self.ws_url.clone();
+        let base_delay = self.config().retry_delay();
+        tokio::spawn(run_price_feed(url, tokens, base_delay, tx));
+
+        Ok(rx)
+    }
+}
+
+async fn run_price_feed(
+    url: String,
+    tokens: TokenMap,
+    base_delay: Duration,
+    tx: mpsc::Sender<MarketPrice>,
+) {
+    let mut failures: u32 = 0;
+
+    while !tx.is_closed() {
+        match connect_async(url.as_str()).await {
+            Ok((mut stream, _)) => {
+                let subscribe = json!({
+                    "assets_ids": tokens.keys().collect::<Vec<_>>(),
+                    "type": "market"
+                });
+                if stream
+                    .send(Message::Text(subscribe.to_string().into()))
+                    .await
+                    .is_ok()
+                {
+                    let connected_at = Instant::now();
+                    let mut ping = tokio::time::interval(PING_INTERVAL);
+
+                    loop {
+                        tokio::select! {
+                            _ = ping.tick() => {
+                                if stream.send(Message::Text("PING".into())).await.is_err() {
+                                    break;
+                                }
+                            }
+                            () = tx.closed() => return,
+                            message = stream.next() => match message {
+                                Some(Ok(Message::Text(text))) => {
+                                    for price in parse_price_messages(&text, &tokens) {
+                                        if tx.send(price).await.is_err() {
+                                            return;
+                                        }
+                                    }
+                                }
+                                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                                Some(Ok(_)) => {}
+                            },
+                        }
+                    }
+                    if connected_at.elapsed() >= STABLE_CONNECTION {
+                        failures = 0;
+                    }
+                }
+                tracing::warn!("Price feed connection to {} closed, reconnecting", url);
+            }
+            Err(e) => {
+                tracing::warn!("Failed to connect to price feed {}: {}", url, e);
+            }
+        }
+
+        failures = failures.saturating_add(1);
+        let backoff = base_delay.saturating_mul(1 << failures.min(16));
+        tokio::time::sleep(backoff.min(MAX_RECONNECT_DELAY)).await;
+    }
+}
+
+/// Extracts price updates for subscribed tokens from a feed message.
+///
+/// Handles `last_trade_price` events and both the legacy (`changes`) and current
+/// (`price_changes`) shapes of `price_change` events. Messages may arrive as a
+/// single object or an array of objects.
+fn parse_price_messages(text: &str, tokens: &TokenMap) -> Vec<MarketPrice> {
+    let Ok(value) = serde_json::from_str::<Value>(text) else {
+        return Vec::new();
+    };
+    let events = match value {
+        Value::Array(events) => events,
+        event => vec![event],
+    };
+
+    let mut prices = Vec::new();
+    for event in &events {
+        let timestamp = event_timestamp(event);
+        match event.get("event_type").and_then(Value::as_str) {
+            Some("last_trade_price") => {
+                prices.extend(price_for(event, event, tokens, &timestamp));
+            }
+            Some("price_change") => {
+                if let Some(changes) = event.get("price_changes").and_then(Value::as_array) {
+                    for change in changes {
+                        prices.extend(price_for(change, change, tokens, &timestamp));
+                    }
+                } else if let Some(changes) = event.get("changes").and_then(Value::as_array) {
+                    for change in changes {
+                        prices.extend(price_for(event, change, tokens, &timestamp));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    prices
+}
+
+/// Builds a [`MarketPrice`] from the asset id in `asset_source` and price in `price_source`.
+fn price_for(
+    asset_source: &Value,
+    price_source: &Value,
+    tokens: &TokenMap,
+    timestamp: &str,
+) -> Option<MarketPrice> {
+    let asset_id = asset_source.get("asset_id")?.as_str()?;
+    let (market_id, outcome, outcome_index) = tokens.get(asset_id)?;
+    let price = match price_source.get("price")? {
+        Value::String(s) => s.parse().ok()?,
+        Value::Number(n) => n.as_f64()?,
+        _ => return None,
+    };
+
+    Some(MarketPrice {
+        market_id: market_id.clone(),
+        outcome_id: outcome.clone(),
+        outcome_index: *outcome_index,
+        price,
+        timestamp: timestamp.to_string(),
+    })
+}
+
+/// Converts the feed's millisecond epoch timestamp to RFC3339, defaulting to now.
+fn event_timestamp(event: &Value) -> String {
+    event
+        .get("timestamp")
+        .and_then(|ts| match ts {
+            Value::String(s) => s.parse::<i64>().ok(),
+            Value::Number(n) => n.as_i64(),
+            _ => None,
+        })
+        .and_then(chrono::DateTime::from_timestamp_millis)
+        .unwrap_or_else(chrono::Utc::now)
+        .to_rfc3339()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use std::sync::Arc;
+    use tokio::net::TcpListener;
+
+    fn token_map() -> TokenMap {
+        TokenMap::from([
+            (
+                "tok-yes".to_string(),
+                ("1".to_string(), "Yes".to_string(), 0),
+            ),
+            ("tok-no".to_string(), ("1".to_string(), "No".to_string(), 1)),
+        ])
+    }
+
+    #[test]
+    fn test_parse_price_messages() {
+        let text = r#"[
+            {"event_type":"price_change","asset_id":"tok-yes","timestamp":"1704067200000","changes":[{"price":"0.55","side":"BUY","size":"10"}]},
+            {"event_type":"last_trade_price","asset_id":"tok-no","price":"0.45","timestamp":"1704067200000"},
+            {"event_type":"book","asset_id":"tok-yes"},
+            {"event_type":"last_trade_price","asset_id":"unknown","price":"0.1"}
+        ]"#;
+
+        let prices = parse_price_messages(text, &token_map());
+
+        assert_eq!(prices.len(), 2);
+        assert_eq!(prices[0].outcome_id, "Yes");
+        assert_eq!(prices[0].price, 0.55);
+        assert_eq!(prices[1].outcome_id, "No");
+        assert_eq!(prices[1].outcome_index, 1);
+        assert!(prices[1].timestamp.starts_with("2024-01-01T00:00:00"));
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_prices_against_mock_server() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let ws_addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (socket, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(socket).await.unwrap();
+            let subscribe = ws.next().await.unwrap().unwrap();
+            assert!(subscribe.to_text().unwrap().contains("tok-yes"));
+            ws.send(Message::Text(
+                r#"{"event_type":"last_trade_price","asset_id":"tok-yes","price":"0.61"}"#.into(),
+            ))
+            .await
+            .unwrap();
+            // Keep the connection open until the client goes away
+            while ws.next().await.is_some() {}
+        });
+
+        let mut http = mockito::Server::new_async().await;
+        http.mock("GET", "/markets/1")
+            .with_body(
+                json!({
+                    "id": "1",
+                    "slug": "market-1",
+                    "question": "Will it happen?",
+                    "active": true,
+                    "closed": false,
+                    "liquidity": "1000",
+                    "volume": "5000",
+                    "endDate": "2030-01-01T00:00:00Z",
+                    "outcomes": "[\"Yes\", \"No\"]",
+                    "outcomePrices": "[\"0.6\", \"0.4\"]",
+                    "clobTokenIds": "[\"tok-yes\", \"tok-no\"]"
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let mut config = Config::default();
+        config.api.base_url = http.url();
+        let mut client = PolymarketClient::new_with_config(&Arc::new(config)).unwrap();
+        client.ws_url = format!("ws://{ws_addr}");
+
+        let mut rx = client
+            .subscribe_prices(vec!["1".to_string()])
+            .await
+            .unwrap();
+        let price = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(price.market_id, "1");
+        assert_eq!(price.outcome_id, "Yes");
+        assert_eq!(price.price, 0.61);
+    }
+}
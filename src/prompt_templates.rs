@@ -0,0 +1,294 @@
+//! User-defined prompts loaded from TOML or JSON files in `prompts_dir`.
+//!
+//! Each file defines one prompt:
+//!
+//! ```toml
+//! name = "election_outlook"
+//! description = "Assess the markets for an upcoming election"
+//! template = "Summarize the markets about {race}. Focus on {focus}."
+//!
+//! [[arguments]]
+//! name = "race"
+//! description = "The race to look at"
+//! required = true
+//!
+//! [[arguments]]
+//! name = "focus"
+//! description = "What to emphasize"
+//! required = false
+//! ```
+//!
+//! `{arg}` placeholders are replaced with the argument's value; an optional argument
+//! that was not given renders as an empty string. Write `{{` and `}}` for literal braces.
+
+use crate::error::{PolymarketError, Result};
+use crate::models::{McpPrompt, McpPromptArgument};
+use config::{Config as ConfigBuilder, File};
+use serde::Deserialize;
+use serde_json::Value;
+use std::path::Path;
+
+/// File extensions read from the prompts directory
+const TEMPLATE_EXTENSIONS: &[&str] = &["toml", "json"];
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PromptTemplate {
+    pub name: String,
+    pub description: String,
+    #[serde(default)]
+    pub arguments: Vec<McpPromptArgument>,
+    pub template: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Literal(String),
+    Placeholder(String),
+}
+
+impl PromptTemplate {
+    /// Checks the template parses and references only declared arguments.
+    ///
+    /// # Errors
+    ///
+    /// Returns a message naming the problem: an empty name, an unbalanced brace, or
+    /// a placeholder with no matching argument.
+    pub fn validate(&self) -> std::result::Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("prompt name cannot be empty".to_string());
+        }
+        for segment in parse(&self.template)? {
+            if let Segment::Placeholder(name) = segment {
+                if !self.arguments.iter().any(|argument| argument.name == name) {
+                    return Err(format!("template references undeclared argument '{name}'"));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Renders the template with `args`, a JSON object of argument values.
+    ///
+    /// Strings are inserted as-is and other values as JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PolymarketError::InvalidArgument`] when a required argument is missing.
+    pub fn render(&self, args: &Value) -> Result<String> {
+        for argument in self.arguments.iter().filter(|argument| argument.required) {
+            if args.get(&argument.name).is_none_or(Value::is_null) {
+                return Err(PolymarketError::invalid_argument(
+                    &argument.name,
+                    "argument is required",
+                ));
+            }
+        }
+
+        let segments = parse(&self.template).map_err(|e| {
+            PolymarketError::config_error(format!("Invalid template for prompt {}: {e}", self.name))
+        })?;
+        let mut text = String::new();
+        for segment in segments {
+            match segment {
+                Segment::Literal(literal) => text.push_str(&literal),
+                Segment::Placeholder(name) => match args.get(&name) {
+                    Some(Value::String(value)) => text.push_str(value),
+                    Some(Value::Null) | None => {}
+                    Some(value) => text.push_str(&value.to_string()),
+                },
+            }
+        }
+        Ok(text)
+    }
+
+    #[must_use]
+    pub fn to_prompt(&self) -> McpPrompt {
+        McpPrompt {
+            name: self.name.clone(),
+            description: self.description.clone(),
+            arguments: self.arguments.clone(),
+        }
+    }
+}
+
+/// Loads and validates every `.toml` and `.json` file in `dir`, in file name order.
+///
+/// # Errors
+///
+/// Returns a configuration error when the directory can't be read, a file doesn't
+/// parse or fails [`PromptTemplate::validate`], or two files define the same name.
+pub fn load_dir(dir: &Path) -> Result<Vec<PromptTemplate>> {
+    let entries = std::fs::read_dir(dir).map_err(|e| {
+        PolymarketError::config_error(format!(
+            "Failed to read prompts directory {}: {e}",
+            dir.display()
+        ))
+    })?;
+    let mut paths: Vec<_> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .is_some_and(|ext| TEMPLATE_EXTENSIONS.contains(&ext))
+        })
+        .collect();
+    paths.sort();
+
+    let mut templates: Vec<PromptTemplate> = Vec::with_capacity(paths.len());
+    for path in paths {
+        let invalid = |reason: String| {
+            PolymarketError::config_error(format!(
+                "Invalid prompt template {}: {reason}",
+                path.display()
+            ))
+        };
+        let template: PromptTemplate = ConfigBuilder::builder()
+            .add_source(File::from(path.as_path()))
+            .build()
+            .and_then(ConfigBuilder::try_deserialize)
+            .map_err(|e| invalid(e.to_string()))?;
+        template.validate().map_err(invalid)?;
+        if templates
+            .iter()
+            .any(|existing| existing.name == template.name)
+        {
+            return Err(invalid(format!(
+                "prompt '{}' is already defined",
+                template.name
+            )));
+        }
+        templates.push(template);
+    }
+    Ok(templates)
+}
+
+/// Splits a template into literal text and `{name}` placeholders.
+fn parse(template: &str) -> std::result::Result<Vec<Segment>, String> {
+    let mut segments = Vec::new();
+    let mut literal = String::new();
+    let mut chars = template.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                literal.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                literal.push('}');
+            }
+            '{' => {
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) if c.is_ascii_alphanumeric() || c == '_' => name.push(c),
+                        Some(c) => {
+                            return Err(format!(
+                                "unexpected '{c}' in placeholder; use {{{{ for a literal brace"
+                            ))
+                        }
+                        None => return Err("unclosed '{' in template".to_string()),
+                    }
+                }
+                if name.is_empty() {
+                    return Err("empty placeholder '{}' in template".to_string());
+                }
+                segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                segments.push(Segment::Placeholder(name));
+            }
+            '}' => return Err("unmatched '}' in template; use }} for a literal brace".to_string()),
+            c => literal.push(c),
+        }
+    }
+    segments.push(Segment::Literal(literal));
+    Ok(segments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn template(text: &str) -> PromptTemplate {
+        PromptTemplate {
+            name: "outlook".to_string(),
+            description: "Outlook for a race".to_string(),
+            arguments: vec![
+                McpPromptArgument {
+                    name: "race".to_string(),
+                    description: "The race".to_string(),
+                    required: true,
+                },
+                McpPromptArgument {
+                    name: "focus".to_string(),
+                    description: "What to emphasize".to_string(),
+                    required: false,
+                },
+            ],
+            template: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_render_substitutes_arguments() {
+        let prompt = template("Look at {race} ({{odds}}). {focus}");
+        prompt.validate().unwrap();
+
+        let text = prompt.render(&json!({"race": "Ohio Senate"})).unwrap();
+        assert_eq!(text, "Look at Ohio Senate ({odds}). ");
+        let text = prompt
+            .render(&json!({"race": "Ohio Senate", "focus": 3}))
+            .unwrap();
+        assert_eq!(text, "Look at Ohio Senate ({odds}). 3");
+
+        let error = prompt.render(&json!({})).unwrap_err();
+        assert_eq!(error.to_string(), "race argument is required");
+    }
+
+    #[test]
+    fn test_load_dir_reads_toml_and_json() {
+        let dir = std::env::temp_dir().join(format!("prompts-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir(&dir).unwrap();
+        std::fs::write(
+            dir.join("b_outlook.toml"),
+            "name = \"outlook\"\ndescription = \"Outlook\"\ntemplate = \"About {race}\"\n\n[[arguments]]\nname = \"race\"\ndescription = \"The race\"\nrequired = true\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("a_daily.json"),
+            json!({"name": "daily", "description": "Daily brief", "template": "Brief me"})
+                .to_string(),
+        )
+        .unwrap();
+        std::fs::write(dir.join("notes.txt"), "ignored").unwrap();
+
+        let templates = load_dir(&dir).unwrap();
+        let names: Vec<&str> = templates.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["daily", "outlook"]);
+        assert!(templates[1].arguments[0].required);
+
+        std::fs::write(
+            dir.join("c_broken.json"),
+            json!({"name": "broken", "description": "", "template": "{missing}"}).to_string(),
+        )
+        .unwrap();
+        let error = load_dir(&dir).unwrap_err().to_string();
+        assert!(error.contains("c_broken.json"), "{error}");
+        assert!(error.contains("undeclared argument 'missing'"), "{error}");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_templates() {
+        assert!(template("About {region}").validate().is_err());
+        assert!(template("About {race").validate().is_err());
+        assert!(template("About race}").validate().is_err());
+        assert!(template("About {}").validate().is_err());
+        assert!(template("About { race }").validate().is_err());
+    }
+}
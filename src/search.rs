@@ -0,0 +1,347 @@
+//! Keyword matching used by market search.
+
+use crate::models::Market;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::str::FromStr;
+
+/// Bonus added to the similarity of two markets grouped under the same event
+const SHARED_EVENT_BONUS: f64 = 0.3;
+/// Bonus added to the similarity of two markets in the same category
+const SAME_CATEGORY_BONUS: f64 = 0.1;
+/// Words too common in market questions to indicate that two markets are related
+const STOP_WORDS: &[&str] = &[
+    "a", "an", "and", "be", "by", "for", "in", "is", "of", "on", "or", "the", "to", "will",
+];
+
+/// How a search keyword is matched against market text.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchMode {
+    /// The whole keyword must appear as a substring of a field
+    #[default]
+    Contains,
+    /// Every whitespace-separated token must appear somewhere in the market
+    AllTokens,
+    /// Like `AllTokens`, but tokens also match words within a small edit distance
+    Fuzzy,
+}
+
+impl FromStr for MatchMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "contains" => Ok(Self::Contains),
+            "all_tokens" => Ok(Self::AllTokens),
+            "fuzzy" => Ok(Self::Fuzzy),
+            other => Err(format!(
+                "Invalid match_mode '{other}'. Valid modes: contains, all_tokens, fuzzy"
+            )),
+        }
+    }
+}
+
+/// How several search keywords combine.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeywordMode {
+    /// A market matches if any keyword matches (OR)
+    #[default]
+    Any,
+    /// A market matches only if every keyword matches (AND)
+    All,
+}
+
+impl FromStr for KeywordMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "any" => Ok(Self::Any),
+            "all" => Ok(Self::All),
+            other => Err(format!("Invalid mode '{other}'. Valid modes: any, all")),
+        }
+    }
+}
+
+/// Liquidity and volume bounds applied server-side while scanning for search matches.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct SearchFilters {
+    pub min_liquidity: Option<f64>,
+    pub max_liquidity: Option<f64>,
+    pub min_volume: Option<f64>,
+}
+
+impl SearchFilters {
+    /// Checks that each range is non-empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns a message naming the offending bounds when a minimum exceeds its maximum.
+    pub fn validate(&self) -> Result<(), String> {
+        if let (Some(min), Some(max)) = (self.min_liquidity, self.max_liquidity) {
+            if min > max {
+                return Err(format!(
+                    "min_liquidity ({min}) must not exceed max_liquidity ({max})"
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Scores `market` against `keyword`, returning the number of matched fields.
+///
+/// Returns `None` when the market does not match at all. Fields considered are the
+/// question, description, and category.
+pub fn match_score(market: &Market, keyword: &str, mode: MatchMode) -> Option<usize> {
+    let fields: Vec<String> = [
+        Some(market.question.as_str()),
+        market.description.as_deref(),
+        market.category.as_deref(),
+    ]
+    .into_iter()
+    .flatten()
+    .map(str::to_lowercase)
+    .collect();
+    let keyword = keyword.to_lowercase();
+
+    let score = match mode {
+        MatchMode::Contains => fields.iter().filter(|f| f.contains(&keyword)).count(),
+        MatchMode::AllTokens | MatchMode::Fuzzy => {
+            let tokens: Vec<&str> = keyword.split_whitespace().collect();
+            if tokens.is_empty() {
+                return None;
+            }
+            let fuzzy = mode == MatchMode::Fuzzy;
+            let all_found = tokens
+                .iter()
+                .all(|token| fields.iter().any(|f| token_matches(f, token, fuzzy)));
+            if !all_found {
+                return None;
+            }
+            fields
+                .iter()
+                .filter(|f| tokens.iter().any(|token| token_matches(f, token, fuzzy)))
+                .count()
+        }
+    };
+
+    (score > 0).then_some(score)
+}
+
+/// Scores `market` against several keywords combined by `keyword_mode`, each matched
+/// with `mode` as in [`match_score`].
+///
+/// The score is the sum of the matching keywords' scores, so markets hitting more
+/// keywords rank higher. Returns `None` when no keyword matches, or under
+/// [`KeywordMode::All`] when any keyword fails to match.
+pub fn match_keywords(
+    market: &Market,
+    keywords: &[impl AsRef<str>],
+    keyword_mode: KeywordMode,
+    mode: MatchMode,
+) -> Option<usize> {
+    let mut total = 0;
+    for keyword in keywords {
+        match match_score(market, keyword.as_ref(), mode) {
+            Some(score) => total += score,
+            None if keyword_mode == KeywordMode::All => return None,
+            None => {}
+        }
+    }
+    (total > 0).then_some(total)
+}
+
+/// Splits `text` into its distinct lowercase alphanumeric words.
+pub fn tokenize(text: &str) -> HashSet<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Share of tokens two sets have in common, from 0 (disjoint) to 1 (identical).
+pub fn jaccard(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(b).count() as f64 / union as f64
+}
+
+/// Scores how related `candidate` is to `source`, returning the score and the question
+/// terms they share, sorted.
+///
+/// The score is the [`jaccard`] overlap of their question terms (ignoring common
+/// words such as "will" or "the"), plus a bonus when they belong to the same event or
+/// category.
+pub fn similarity(source: &Market, candidate: &Market) -> (f64, Vec<String>) {
+    let terms = |market: &Market| {
+        let mut tokens = tokenize(&market.question);
+        tokens.retain(|token| !STOP_WORDS.contains(&token.as_str()));
+        tokens
+    };
+    let (source_terms, candidate_terms) = (terms(source), terms(candidate));
+    let mut shared: Vec<String> = source_terms
+        .intersection(&candidate_terms)
+        .cloned()
+        .collect();
+    shared.sort();
+
+    let mut score = jaccard(&source_terms, &candidate_terms);
+    if shares_event(source, candidate) {
+        score += SHARED_EVENT_BONUS;
+    }
+    let category = |market: &Market| market.category.as_deref().map(str::to_lowercase);
+    if category(source).is_some() && category(source) == category(candidate) {
+        score += SAME_CATEGORY_BONUS;
+    }
+    (score, shared)
+}
+
+fn shares_event(a: &Market, b: &Market) -> bool {
+    let ids = |market: &Market| -> HashSet<String> {
+        market
+            .events
+            .iter()
+            .flatten()
+            .map(|event| event.id.clone())
+            .collect()
+    };
+    !ids(a).is_disjoint(&ids(b))
+}
+
+fn token_matches(field: &str, token: &str, fuzzy: bool) -> bool {
+    if field.contains(token) {
+        return true;
+    }
+    if !fuzzy {
+        return false;
+    }
+    // Allow roughly one typo per four characters, but always at least one
+    let max_distance = (token.chars().count() / 4).max(1);
+    field
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .any(|word| levenshtein(word, token) <= max_distance)
+}
+
+/// Classic two-row Levenshtein edit distance over chars.
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0; b.len() + 1];
+
+    for (i, ca) in a.chars().enumerate() {
+        curr[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            curr[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    prev[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn market(question: &str, description: Option<&str>) -> Market {
+        serde_json::from_value(serde_json::json!({
+            "id": "1",
+            "slug": "market-1",
+            "question": question,
+            "description": description,
+            "active": true,
+            "closed": false,
+            "liquidity": "1000",
+            "volume": "5000",
+            "endDate": "2030-01-01T00:00:00Z",
+            "outcomes": "[\"Yes\", \"No\"]",
+            "outcomePrices": "[\"0.6\", \"0.4\"]",
+            "category": "Politics"
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_levenshtein() {
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+        assert_eq!(levenshtein("", "abc"), 3);
+        assert_eq!(levenshtein("same", "same"), 0);
+    }
+
+    #[test]
+    fn test_match_modes() {
+        let m = market(
+            "Who will win the Presidential Election?",
+            Some("Resolves on the election result"),
+        );
+
+        assert_eq!(match_score(&m, "pres election", MatchMode::Contains), None);
+        assert_eq!(
+            match_score(&m, "pres election", MatchMode::AllTokens),
+            Some(2)
+        );
+        assert_eq!(match_score(&m, "pres bitcoin", MatchMode::AllTokens), None);
+        assert_eq!(match_score(&m, "electoin", MatchMode::Fuzzy), Some(2));
+        assert_eq!(match_score(&m, "politics", MatchMode::Contains), Some(1));
+    }
+
+    #[test]
+    fn test_similarity_ranks_term_overlap_and_shared_context() {
+        let source = market("Will Bitcoin reach $100k in 2024?", None);
+        let close = market("Will Bitcoin reach $150k in 2024?", None);
+        let mut unrelated = market("Who wins the Super Bowl?", None);
+        unrelated.category = Some("Sports".to_string());
+
+        let (close_score, shared) = similarity(&source, &close);
+        assert_eq!(shared, vec!["2024", "bitcoin", "reach"]);
+        assert!((close_score - (0.6 + SAME_CATEGORY_BONUS)).abs() < 1e-9);
+
+        let (unrelated_score, shared) = similarity(&source, &unrelated);
+        assert_eq!(unrelated_score, 0.0);
+        assert!(shared.is_empty());
+    }
+
+    #[test]
+    fn test_keyword_modes() {
+        let m = market(
+            "Will Bitcoin reach $100k?",
+            Some("Resolves yes if the 2024 high exceeds $100k"),
+        );
+        let contains = MatchMode::Contains;
+
+        // Keywords match across question, description and category
+        assert_eq!(
+            match_keywords(
+                &m,
+                &["bitcoin", "2024", "politics"],
+                KeywordMode::All,
+                contains
+            ),
+            Some(3)
+        );
+        assert_eq!(
+            match_keywords(&m, &["bitcoin", "ethereum"], KeywordMode::All, contains),
+            None
+        );
+        assert_eq!(
+            match_keywords(&m, &["bitcoin", "ethereum"], KeywordMode::Any, contains),
+            Some(1)
+        );
+        assert_eq!(
+            match_keywords(&m, &["solana", "ethereum"], KeywordMode::Any, contains),
+            None
+        );
+        assert_eq!(
+            match_keywords(&m, &[] as &[&str], KeywordMode::All, contains),
+            None
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use polymarket_mcp::{Config, PolymarketClient};
 use serde_json::json;
+use std::collections::HashMap;
 use std::sync::Arc;
 
 #[tokio::test]
@@ -23,6 +24,23 @@ async fn test_client_creation() {
     let config = Arc::new(Config::default());
     let client = PolymarketClient::new_with_config(&config);
     assert!(client.is_ok(), "Client should be created successfully");
+
+    let mut config = Config::default();
+    config.api.pool_max_idle_per_host = 0;
+    config.api.pool_idle_timeout_seconds = 5;
+    let client = PolymarketClient::new_with_config(&Arc::new(config));
+    assert!(
+        client.is_ok(),
+        "Client should build with a custom connection pool"
+    );
+
+    let mut config = Config::default();
+    config.api.http2_prior_knowledge = true;
+    let client = PolymarketClient::new_with_config(&Arc::new(config));
+    assert!(
+        client.is_ok(),
+        "Client should build with HTTP/2 prior knowledge"
+    );
 }
 
 #[test]
@@ -47,6 +65,26 @@ fn test_config_structure() {
         "Server timeout should be positive"
     );
     assert!(config.cache.ttl_seconds > 0, "Cache TTL should be positive");
+    assert_eq!(config.api.pool_max_idle_per_host, 10);
+    assert_eq!(
+        config.pool_idle_timeout(),
+        std::time::Duration::from_secs(30)
+    );
+}
+
+#[test]
+fn test_endpoint_urls_resolve_per_host() {
+    let mut config = Config::default();
+    assert_eq!(config.gamma_url(), "https://gamma-api.polymarket.com");
+    assert_eq!(config.clob_url(), "https://clob.polymarket.com");
+    assert_eq!(config.data_api_url(), "https://data-api.polymarket.com");
+
+    // A customized base_url keeps serving every host unless overridden
+    config.api.base_url = "http://localhost:8080".to_string();
+    config.endpoints.clob_url = Some("http://localhost:9090".to_string());
+    assert_eq!(config.gamma_url(), "http://localhost:8080");
+    assert_eq!(config.clob_url(), "http://localhost:9090");
+    assert_eq!(config.data_api_url(), "http://localhost:8080");
 }
 
 #[test]
@@ -180,6 +218,378 @@ fn test_resource_cache() {
     );
 }
 
+fn sample_market(outcome_prices: &str) -> polymarket_mcp::Market {
+    serde_json::from_value(json!({
+        "id": "m1",
+        "slug": "sample-market",
+        "question": "Will the sample resolve yes?",
+        "active": true,
+        "closed": false,
+        "liquidity": "2500",
+        "volume": "10000",
+        "endDate": "2030-01-01T00:00:00Z",
+        "outcomes": "[\"Yes\", \"No\"]",
+        "outcomePrices": outcome_prices
+    }))
+    .expect("sample market should deserialize")
+}
+
+#[test]
+fn test_market_outcome_lists_accept_strings_and_arrays() {
+    let encoded = sample_market("[\"0.6\", \"0.4\"]");
+    assert_eq!(encoded.outcomes, vec!["Yes", "No"]);
+    assert_eq!(encoded.outcome_prices, vec!["0.6", "0.4"]);
+
+    let market: polymarket_mcp::Market = serde_json::from_value(json!({
+        "id": "m2",
+        "slug": "array-market",
+        "question": "Does the array shape parse?",
+        "active": true,
+        "closed": false,
+        "liquidity": "2500",
+        "volume": "10000",
+        "endDate": "2030-01-01T00:00:00Z",
+        "outcomes": ["Yes", "No"],
+        "outcomePrices": [0.25, "0.75"],
+        "clobTokenIds": ["tok-yes", "tok-no"]
+    }))
+    .expect("array-shaped lists should deserialize");
+    assert_eq!(market.outcomes, vec!["Yes", "No"]);
+    assert_eq!(market.outcome_prices, vec!["0.25", "0.75"]);
+    assert_eq!(
+        market.clob_token_ids,
+        Some(vec!["tok-yes".to_string(), "tok-no".to_string()])
+    );
+
+    // Markets we serialized ourselves, e.g. the persisted cache, carry real arrays
+    let reread: polymarket_mcp::Market =
+        serde_json::from_value(serde_json::to_value(&market).unwrap())
+            .expect("serialized markets should deserialize");
+    assert_eq!(reread.outcomes, market.outcomes);
+    assert_eq!(reread.outcome_prices, market.outcome_prices);
+    assert_eq!(reread.clob_token_ids, market.clob_token_ids);
+
+    let invalid = serde_json::from_value::<polymarket_mcp::Market>(json!({
+        "id": "m3",
+        "slug": "bad-market",
+        "question": "Bad?",
+        "active": true,
+        "closed": false,
+        "liquidity": "1",
+        "volume": "1",
+        "endDate": "2030-01-01T00:00:00Z",
+        "outcomes": {"yes": true},
+        "outcomePrices": "[]"
+    }));
+    assert!(invalid.is_err());
+}
+
+fn market_with_amounts(
+    liquidity: serde_json::Value,
+    volume: serde_json::Value,
+) -> serde_json::Value {
+    json!({
+        "id": "m4",
+        "slug": "amount-market",
+        "question": "Do amounts parse?",
+        "active": true,
+        "closed": false,
+        "liquidity": liquidity,
+        "volume": volume,
+        "endDate": "2030-01-01T00:00:00Z",
+        "outcomes": "[\"Yes\", \"No\"]",
+        "outcomePrices": "[\"0.5\", \"0.5\"]"
+    })
+}
+
+#[test]
+fn test_structured_outcomes() {
+    let outcomes = sample_market("[\"0.55\", \"0.5\"]").structured_outcomes();
+    assert_eq!(outcomes.len(), 2);
+    assert_eq!(outcomes[1].index, 1);
+    assert_eq!(outcomes[1].name, "No");
+    assert_eq!(outcomes[1].price, Some(0.5));
+    assert!((outcomes[0].implied_probability.unwrap() - 0.55 / 1.05).abs() < 1e-9);
+
+    // Mismatched arrays are zipped to the shorter one
+    let outcomes = sample_market("[\"0.7\"]").structured_outcomes();
+    assert_eq!(outcomes.len(), 1);
+    assert_eq!(outcomes[0].implied_probability, Some(1.0));
+}
+
+#[test]
+fn test_market_amounts_accept_strings_and_numbers() {
+    use polymarket_mcp::Market;
+
+    let market: Market =
+        serde_json::from_value(market_with_amounts(json!("1234.5"), json!(98765))).unwrap();
+    assert_eq!(market.liquidity, 1234.5);
+    assert_eq!(market.volume, 98765.0);
+
+    let market: Market =
+        serde_json::from_value(market_with_amounts(json!(0.25), json!(""))).unwrap();
+    assert_eq!(market.liquidity, 0.25);
+    assert_eq!(market.volume, 0.0);
+
+    // Our own serialized markets carry plain numbers
+    let reread: Market = serde_json::from_value(serde_json::to_value(&market).unwrap()).unwrap();
+    assert_eq!(reread.liquidity, 0.25);
+
+    let err = serde_json::from_value::<Market>(market_with_amounts(json!("lots"), json!("1")))
+        .unwrap_err();
+    assert!(err.to_string().contains("Invalid numeric string 'lots'"));
+
+    // One numeric market must not break parsing of the whole list
+    let markets: Vec<Market> = serde_json::from_value(json!([
+        market_with_amounts(json!("10"), json!("20")),
+        market_with_amounts(json!(10), json!(20.5)),
+    ]))
+    .unwrap();
+    assert_eq!(markets.len(), 2);
+    assert_eq!(markets[1].volume, 20.5);
+}
+
+#[test]
+fn test_resolved_outcome() {
+    let mut market = sample_market("[\"0.995\", \"0.005\"]");
+    assert_eq!(
+        market.resolved_outcome(),
+        None,
+        "open markets are unresolved"
+    );
+
+    market.closed = true;
+    assert_eq!(market.resolved_outcome(), Some("Yes".to_string()));
+
+    market.outcome_prices = vec!["0".to_string(), "1".to_string()];
+    assert_eq!(market.resolved_outcome(), Some("No".to_string()));
+
+    market.outcome_prices = vec!["0.6".to_string(), "0.4".to_string()];
+    assert_eq!(market.resolved_outcome(), None);
+
+    market.outcome_prices = vec!["1".to_string(), "1".to_string()];
+    assert_eq!(market.resolved_outcome(), None);
+}
+
+#[test]
+fn test_parsed_end_date() {
+    use chrono::{TimeZone, Utc};
+
+    let mut market = sample_market("[\"0.5\", \"0.5\"]");
+    assert_eq!(
+        market.parsed_end_date(),
+        Some(Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap())
+    );
+
+    let now = Utc.with_ymd_and_hms(2029, 12, 31, 12, 0, 0).unwrap();
+    assert_eq!(market.time_to_resolution_hours(now), Some(12.0));
+
+    market.end_date = "2030-01-01T02:00:00+02:00".to_string();
+    assert_eq!(market.time_to_resolution_hours(now), Some(12.0));
+
+    market.end_date = "January 1st".to_string();
+    assert_eq!(market.parsed_end_date(), None);
+    assert_eq!(market.time_to_resolution_hours(now), None);
+    assert!(!market.expires_soon());
+}
+
+fn sample_trade(outcome: &str, price: f64, timestamp: &str, trader: &str) -> polymarket_mcp::Trade {
+    serde_json::from_value(json!({
+        "id": format!("{outcome}-{timestamp}"),
+        "market_id": "m1",
+        "outcome_id": outcome,
+        "side": "buy",
+        "size": 10.0,
+        "price": price,
+        "timestamp": timestamp,
+        "trader_address": trader
+    }))
+    .expect("sample trade should deserialize")
+}
+
+#[test]
+fn test_market_stats_from_trades() {
+    use polymarket_mcp::MarketStats;
+
+    let market = sample_market("[\"0.7\", \"0.3\"]");
+    let now = chrono::DateTime::parse_from_rfc3339("2024-06-02T00:00:00Z")
+        .unwrap()
+        .with_timezone(&chrono::Utc);
+    let trades = vec![
+        sample_trade("Yes", 0.6, "2024-06-01T01:00:00Z", "0xa"),
+        sample_trade("No", 0.2, "2024-06-01T12:00:00Z", "0xb"),
+        sample_trade("Yes", 0.7, "2024-06-01T20:00:00Z", "0xa"),
+        sample_trade("Yes", 0.1, "2024-05-30T00:00:00Z", "0xc"),
+    ];
+
+    let stats = MarketStats::from_trades(&market, &trades, now);
+
+    assert!((stats.high_24h - 0.8).abs() < 1e-9);
+    assert!((stats.low_24h - 0.6).abs() < 1e-9);
+    assert!((stats.price_change_24h - 0.1).abs() < 1e-9);
+    assert_eq!(stats.num_traders, Some(2));
+    assert_eq!(stats.liquidity, 2500.0);
+}
+
+#[test]
+fn test_markets_to_csv_quotes_fields_and_picks_favorite() {
+    use polymarket_mcp::export::markets_to_csv;
+
+    let mut market = sample_market("[\"0.3\", \"0.7\"]");
+    market.question = "Will \"X\" win, or not?".to_string();
+    market.category = None;
+    market.volume_24hr = None;
+
+    let csv = markets_to_csv(&[market.clone()]);
+    let lines: Vec<&str> = csv.split("\r\n").collect();
+    assert_eq!(
+        lines[0],
+        "id,slug,question,category,liquidity,volume,volume_24hr,end_date,favorite_outcome,favorite_price"
+    );
+    assert_eq!(
+        lines[1],
+        format!(
+            "{},{},\"Will \"\"X\"\" win, or not?\",,{},{},,{},{},0.7",
+            market.id,
+            market.slug,
+            market.liquidity,
+            market.volume,
+            market.end_date,
+            market.outcomes[1]
+        )
+    );
+    assert_eq!(lines[2], "");
+}
+
+#[test]
+fn test_notability_prefers_surging_markets_over_large_quiet_ones() {
+    use polymarket_mcp::config::AnalyticsConfig;
+    use polymarket_mcp::notability;
+
+    let now = chrono::Utc::now();
+    let market = |id: &str, liquidity: f64, volume: f64, volume_24hr: f64, days_left: i64| {
+        let mut market = sample_market("[\"0.5\", \"0.5\"]");
+        market.id = id.to_string();
+        market.liquidity = liquidity;
+        market.volume = volume;
+        market.volume_24hr = Some(volume_24hr);
+        market.end_date = (now + chrono::Duration::days(days_left)).to_rfc3339();
+        market
+    };
+    let markets = vec![
+        market("giant", 5_000_000.0, 100_000_000.0, 50_000.0, 300),
+        market("surging", 200_000.0, 400_000.0, 200_000.0, 2),
+        market("expired", 1_000.0, 10_000.0, 0.0, -1),
+    ];
+
+    let analytics = AnalyticsConfig::default();
+    let ranked = notability::rank(markets, &analytics, now, 2);
+    let ids: Vec<&str> = ranked.iter().map(|n| n.market.id.as_str()).collect();
+    assert_eq!(ids, vec!["surging", "giant"]);
+
+    let giant = &ranked[1].components;
+    assert_eq!(giant.liquidity, 1.0);
+    assert!((giant.volume_surge - 0.0005).abs() < 1e-9);
+    // Two days out, against a one-week half-life
+    assert!((ranked[0].components.time_to_close - 168.0 / 216.0).abs() < 1e-3);
+    assert!((ranked[0].score - ranked[0].components.score(&analytics)).abs() < 1e-12);
+}
+
+#[test]
+fn test_market_overview_aggregates_sample() {
+    use polymarket_mcp::MarketOverview;
+
+    let market = |liquidity: f64, volume_24h: Option<f64>, category: Option<&str>| {
+        let mut market = sample_market("[\"0.5\", \"0.5\"]");
+        market.liquidity = liquidity;
+        market.volume_24hr = volume_24h;
+        market.category = category.map(str::to_string);
+        market
+    };
+    let markets = vec![
+        market(100.0, Some(10.0), Some("Sports")),
+        market(400.0, None, Some("Politics")),
+        market(300.0, Some(5.0), Some("Sports")),
+        market(200.0, Some(1.0), None),
+    ];
+
+    let overview = MarketOverview::from_markets(&markets, 200);
+    assert_eq!(overview.sample_size, 200);
+    assert_eq!(overview.active_markets, 4);
+    assert_eq!(overview.total_liquidity, 1000.0);
+    assert_eq!(overview.total_volume_24h, 16.0);
+    assert_eq!(overview.median_liquidity, 250.0);
+    assert_eq!(overview.top_category.as_deref(), Some("Sports"));
+    assert_eq!(overview.top_category_count, 2);
+
+    let empty = MarketOverview::from_markets(&[], 200);
+    assert_eq!(empty.median_liquidity, 0.0);
+    assert_eq!(empty.top_category, None);
+}
+
+#[test]
+fn test_market_stats_without_trades() {
+    use polymarket_mcp::MarketStats;
+
+    let market = sample_market("[\"0.7\", \"0.3\"]");
+    let stats = MarketStats::from_trades(&market, &[], chrono::Utc::now());
+
+    assert_eq!(stats.high_24h, 0.7);
+    assert_eq!(stats.low_24h, 0.7);
+    assert_eq!(stats.price_change_24h, 0.0);
+    assert_eq!(stats.num_traders, None);
+}
+
+fn sample_book(bids: &[(f64, f64)], asks: &[(f64, f64)]) -> polymarket_mcp::OrderBook {
+    use polymarket_mcp::{OrderBook, OrderBookLevel};
+
+    let levels = |levels: &[(f64, f64)]| {
+        levels
+            .iter()
+            .map(|&(price, size)| OrderBookLevel { price, size })
+            .collect()
+    };
+    OrderBook {
+        market_id: "1".to_string(),
+        outcome_id: "Yes".to_string(),
+        bids: levels(bids),
+        asks: levels(asks),
+        timestamp: chrono::Utc::now().to_rfc3339(),
+    }
+}
+
+#[test]
+fn test_order_book_spread_and_depth() {
+    let book = sample_book(
+        &[(0.495, 100.0), (0.48, 50.0), (0.40, 500.0)],
+        &[(0.505, 80.0), (0.60, 300.0)],
+    );
+
+    assert!((book.spread().unwrap() - 0.01).abs() < 1e-9);
+    assert!((book.mid().unwrap() - 0.5).abs() < 1e-9);
+
+    // 2% of a 0.50 mid keeps prices in [0.49, 0.51]
+    let depth = book.depth_within(2.0);
+    assert_eq!(depth.bids, Some(100.0));
+    assert_eq!(depth.asks, Some(80.0));
+
+    let depth = book.depth_within(5.0);
+    assert_eq!(depth.bids, Some(150.0));
+    assert_eq!(depth.asks, Some(80.0));
+}
+
+#[test]
+fn test_one_sided_order_book_depth() {
+    let book = sample_book(&[(0.50, 10.0), (0.495, 20.0), (0.30, 40.0)], &[]);
+
+    assert_eq!(book.spread(), None);
+    assert_eq!(book.mid(), None);
+
+    let depth = book.depth_within(2.0);
+    assert_eq!(depth.bids, Some(30.0));
+    assert_eq!(depth.asks, None);
+}
+
 #[cfg(test)]
 mod mcp_protocol_tests {
     use super::*;
@@ -243,3 +653,129 @@ mod mcp_protocol_tests {
         );
     }
 }
+
+#[test]
+fn test_config_validation_names_bad_field() {
+    assert!(Config::default().validate().is_ok());
+
+    let mut config = Config::default();
+    config.api.max_retries = 0;
+    let error = config.validate().unwrap_err().to_string();
+    assert!(error.contains("max_retries"), "{error}");
+
+    let mut config = Config::default();
+    config.api.base_url = String::new();
+    let error = config.validate().unwrap_err().to_string();
+    assert!(error.contains("base_url"), "{error}");
+
+    let mut config = Config::default();
+    config.api.retry_delay_ms = 0;
+    let error = config.validate().unwrap_err().to_string();
+    assert!(error.contains("retry_delay_ms"), "{error}");
+
+    let mut config = Config::default();
+    config.cache.ttl_seconds = 0;
+    let error = config.validate().unwrap_err().to_string();
+    assert!(error.contains("ttl_seconds"), "{error}");
+
+    let mut config = Config::default();
+    config.logging.level = "verbose".to_string();
+    let error = config.validate().unwrap_err().to_string();
+    assert!(error.contains("logging.level 'verbose'"), "{error}");
+}
+
+#[test]
+fn test_load_from_file_rejects_invalid_values() {
+    let path = std::env::temp_dir().join(format!("polymarket-mcp-{}.json", uuid::Uuid::new_v4()));
+    let mut config = Config::default();
+    config.api.max_retries = 0;
+    std::fs::write(&path, serde_json::to_string(&config).unwrap()).unwrap();
+
+    let error = Config::load_from_file(path.to_str().unwrap()).unwrap_err();
+    std::fs::remove_file(&path).unwrap();
+    assert!(format!("{error:#}").contains("max_retries"), "{error:#}");
+}
+
+#[test]
+fn test_env_overrides_file_values() {
+    let path = std::env::temp_dir().join(format!("polymarket-mcp-{}.json", uuid::Uuid::new_v4()));
+    let mut file_config = Config::default();
+    file_config.api.batch_delay_ms = 250;
+    file_config.api.search_scan_limit = 300;
+    std::fs::write(&path, serde_json::to_string(&file_config).unwrap()).unwrap();
+
+    let env = HashMap::from([("POLYMARKET_API_BATCH_DELAY_MS", "750")]);
+    let config = Config::load_with_file_and_env(path.to_str().unwrap(), |name| {
+        env.get(name).map(|val| (*val).to_string())
+    });
+    std::fs::remove_file(&path).unwrap();
+
+    let config = config.unwrap();
+    assert_eq!(config.api.batch_delay_ms, 750);
+    assert_eq!(config.api.search_scan_limit, 300);
+}
+
+#[test]
+fn test_deprecated_rate_limit_applies_only_without_requests_per_second() {
+    let mut config = Config::default();
+    assert_eq!(config.requests_per_second(), 0);
+
+    config.api.rate_limit_per_second = Some(5);
+    assert_eq!(config.requests_per_second(), 5);
+
+    config.api.requests_per_second = 3;
+    assert_eq!(config.requests_per_second(), 3);
+}
+
+#[test]
+fn test_resource_ttl_falls_back_to_global() {
+    use std::time::Duration;
+
+    let mut config = Config::default();
+    config.cache.resource_cache_ttl_seconds = 300;
+    config.cache.trending_ttl_seconds = Some(30);
+    config.cache.market_ttl_seconds = Some(900);
+
+    assert_eq!(
+        config.resource_ttl_for("markets:trending"),
+        Duration::from_secs(30)
+    );
+    assert_eq!(
+        config.resource_ttl_for("market:123"),
+        Duration::from_secs(900)
+    );
+    assert_eq!(
+        config.resource_ttl_for("markets:active"),
+        Duration::from_secs(300)
+    );
+    assert_eq!(
+        config.resource_ttl_for("markets:watchlist"),
+        Duration::from_secs(300)
+    );
+
+    config.cache.active_ttl_seconds = Some(0);
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn test_reload_applies_only_hot_fields() {
+    let running = Config::default();
+    let mut reloaded = Config::default();
+    reloaded.cache.ttl_seconds = 5;
+    reloaded.logging.level = "debug".to_string();
+    reloaded.api.requests_per_second = 3;
+
+    let (merged, ignored) = running.with_reloadable_from(&reloaded);
+    assert_eq!(merged.cache.ttl_seconds, 5);
+    assert_eq!(merged.logging.level, "debug");
+    assert_eq!(merged.api.requests_per_second, 3);
+    assert!(ignored.is_empty(), "{ignored:?}");
+
+    reloaded.api.base_url = "http://localhost:8080".to_string();
+    reloaded.cache.max_entries = 10;
+    let (merged, ignored) = running.with_reloadable_from(&reloaded);
+    assert_eq!(merged.api.base_url, running.api.base_url);
+    assert_eq!(merged.cache.max_entries, running.cache.max_entries);
+    assert_eq!(merged.cache.ttl_seconds, 5);
+    assert_eq!(ignored, vec!["api", "cache"]);
+}